@@ -3,7 +3,7 @@ use concordium_cis2::*;
 use concordium_std::*;
 
 /// The state tracked for each address.
-#[derive(Serialize, SchemaType)]
+#[derive(Serialize, SchemaType, Clone)]
 struct PlayerData {
     /// The player's state
     state: PlayerState,
@@ -11,9 +11,81 @@ struct PlayerData {
     wins: u64,
     /// The player's losses
     losses: u64,
+    /// The player's draws
+    draws: u64,
+    /// The total points scored by the player in battles with a score.
+    score_for: u64,
+    /// The total points scored against the player in battles with a score.
+    score_against: u64,
+    /// The player's skill rating.
+    rating: Rating,
+    /// The time the player was added.
+    registered_at: Timestamp,
+    /// The time of the player's latest battle, if any.
+    last_played: Option<Timestamp>,
+    /// The current streak: the number of battles won in a row if positive,
+    /// or lost in a row if negative. A draw ends any streak.
+    current_streak: i64,
+    /// The longest win streak of the player.
+    best_win_streak: u64,
+    /// The longest losing streak of the player.
+    worst_loss_streak: u64,
+}
+
+/// The version of `PlayerStats`, increased whenever fields are added.
+const PLAYER_STATS_VERSION: u16 = 2;
+
+/// The return type for the contract function `getPlayerStats`.
+#[derive(Serialize, SchemaType)]
+struct PlayerStats {
+    /// The version of this type, see `PLAYER_STATS_VERSION`.
+    version: u16,
+    /// The player's state.
+    state: PlayerState,
+    /// The player's wins.
+    wins: u64,
+    /// The player's losses.
+    losses: u64,
+    /// The player's draws.
+    draws: u64,
+    /// The number of battles the player has played.
+    games_played: u64,
+    /// The share of battles won in basis points.
+    win_rate: u16,
+    /// The total points scored by the player in battles with a score.
+    score_for: u64,
+    /// The total points scored against the player in battles with a score.
+    score_against: u64,
+    /// The player's skill rating.
+    rating: Rating,
+    /// The time the player was added.
+    registered_at: Timestamp,
+    /// The time of the player's latest battle, if any.
+    last_played: Option<Timestamp>,
+    /// The current streak, positive for wins and negative for losses. Added
+    /// in version 2.
+    current_streak: i64,
+    /// The longest win streak of the player. Added in version 2.
+    best_win_streak: u64,
+    /// The longest losing streak of the player. Added in version 2.
+    worst_loss_streak: u64,
 }
 
-/// The parameter type for the state contract function `updatePlayerState`.
+/// The skill rating of a player. All values are fixed-point numbers with
+/// `RATING_SCALE` fractional units, i.e. a rating of 1500 is stored as
+/// `1_500_000_000`.
+#[derive(Debug, Serialize, SchemaType, Clone, Copy, PartialEq, Eq)]
+struct Rating {
+    /// The rating itself.
+    rating: u64,
+    /// The rating deviation. Only used by Glicko-2.
+    deviation: u64,
+    /// The rating volatility. Only used by Glicko-2.
+    volatility: u64,
+}
+
+/// The parameter type for the items of the contract function
+/// `setPlayerDataBatch`.
 #[derive(Serialize, SchemaType)]
 struct UpdatePlayerStateParams {
     /// Player to update state.
@@ -27,626 +99,9653 @@ struct UpdatePlayerStateParams {
 struct UpdateBattleResultParams {
     /// Player to update state.
     player: Address,
-    /// Win or Loss
+    /// Win, Loss or Draw
     result: BattleResult,
+    /// The final score of the battle from the point of view of the player,
+    /// if the game mode keeps a score.
+    score: Option<MatchScore>,
+    /// The player's opponent. If the opponent is not given or not added,
+    /// the player is rated against the initial rating of the rating engine.
+    opponent: Option<Address>,
 }
 
-/// The contract state.
-#[derive(Serial, DeserialWithState, StateClone)]
-#[concordium(state_parameter = "S")]
-struct State<S: HasStateApi> {
-    /// The admin address can upgrade the contract, pause and unpause the
-    /// contract, transfer the admin address to a new address, set
-    /// implementors, and update the metadata URL in the contract.
-    admin: Address,
-    /// The state of the one player.
-    player_data: StateMap<Address, PlayerData, S>,
-    /// Contract is paused/unpaused.
-    paused: bool,
-    /// Map with contract addresses providing implementations of additional
-    /// standards.
-    implementors: StateMap<StandardIdentifierOwned, Vec<ContractAddress>, S>,
+/// The roles that can be granted to addresses. The admin address always
+/// holds the `Admin` role; all other roles can be granted and revoked.
+#[derive(Debug, Serialize, SchemaType, Clone, Copy, PartialEq, Eq)]
+enum Role {
+    /// Can grant and revoke roles and change the league configuration.
+    Admin,
+    /// Can report battle results.
+    Reporter,
+    /// Can add players and change their `PlayerState`.
+    Moderator,
+    /// Can pause and unpause the contract.
+    Pauser,
+    /// Can resolve disputes about battle results.
+    Arbitrator,
 }
 
-#[derive(Debug, Serialize, SchemaType, Clone, Copy, PartialEq)]
-enum PlayerState {
-    Active,
-    Suspended,
-}
+/// The roles granted to the admin when the contract is initialized, which
+/// are revoked from the previous admin when the admin is transferred.
+const OPERATIONAL_ROLES: [Role; 4] =
+    [Role::Reporter, Role::Moderator, Role::Pauser, Role::Arbitrator];
 
-#[derive(Debug, Serialize, SchemaType, Clone, Copy)]
-enum BattleResult {
-    Win,
-    Loss,
+/// The parameter type for the contract functions `grantRole`, `revokeRole`
+/// and `hasRole`.
+#[derive(Debug, Serialize, SchemaType, Clone)]
+struct RoleParams {
+    /// The address to grant the role to, revoke it from or check.
+    address: Address,
+    /// The role.
+    role: Role,
 }
 
-#[derive(Debug, Serialize, SchemaType)]
-struct UpgradeParams {
-    /// The new module reference.
-    module: ModuleReference,
-    /// Optional entrypoint to call in the new module after upgrade.
-    migrate: Option<(OwnedEntrypointName, OwnedParameter)>,
+/// How a batch handles items that cannot be applied.
+#[derive(Debug, Serialize, SchemaType, Clone, Copy, PartialEq, Eq)]
+enum BatchMode {
+    /// Reject the whole batch if any item cannot be applied.
+    AllOrNothing,
+    /// Skip the items that cannot be applied and apply the rest.
+    SkipInvalid,
 }
 
-/// The return type for the contract function `view`.
+/// The parameter type for the contract function `setPlayerDataBatch`.
 #[derive(Serialize, SchemaType)]
-struct ReturnBasicState {
-    /// The admin address can upgrade the contract, pause and unpause the
-    /// contract, transfer the admin address to a new address, set
-    /// implementors, and update the metadata URL in the contract.
-    admin: Address,
-    /// Contract is paused if `paused = true` and unpaused if `paused = false`.
-    paused: bool,
+struct SetPlayerDataBatchParams {
+    /// How to handle items that cannot be applied.
+    mode: BatchMode,
+    /// The players to add or update.
+    updates: Vec<UpdatePlayerStateParams>,
 }
 
-/// The parameter type for the contract function `setPaused`.
+/// The parameter type for the contract function `updateBattleResultBatch`.
 #[derive(Serialize, SchemaType)]
-#[repr(transparent)]
-struct SetPausedParams {
-    /// Contract is paused if `paused = true` and unpaused if `paused = false`.
-    paused: bool,
+struct UpdateBattleResultBatchParams {
+    /// How to handle items that cannot be applied.
+    mode: BatchMode,
+    /// The battle results to record.
+    updates: Vec<UpdateBattleResultParams>,
 }
 
-/// A NewAdminEvent introduced by this smart contract.
-#[derive(Serial, SchemaType)]
-#[repr(transparent)]
-struct NewAdminEvent {
-    /// New admin address.
-    new_admin: Address,
+/// The outcome of one item of a batch.
+#[derive(Debug, Serial, SchemaType, PartialEq, Eq)]
+enum BatchItemOutcome {
+    /// The item was applied.
+    Applied,
+    /// The item was skipped because of the given error.
+    Skipped(CustomContractError),
 }
 
-#[derive(Serialize, SchemaType)]
-struct NewBattleResultEvent {
-    /// Player address.
-    player: Address,
-    /// Player's new battle result.
-    is_win: BattleResult,
-}
+/// Identifier of a battle, assigned by the game server reporting it.
+type BattleId = u64;
 
-/// A BattleResultEvent introduced by this smart contract.
-/// This event is emitted when a player's battle result is updated.
-#[derive(Serial, SchemaType)]
-struct BattleResultEvent {
-    /// Player address.
-    player: Address,
-    /// Player's new battle result.
-    is_win: bool,
+/// The parameter type for the contract function `reportMatch`.
+#[derive(Serialize, SchemaType, Clone)]
+struct ReportMatchParams {
+    /// Unique identifier of the battle.
+    battle_id: BattleId,
+    /// The player who won the battle.
+    winner: Address,
+    /// The player who lost the battle.
+    loser: Address,
+    /// The battle ended in a draw. The `winner` and `loser` then only name
+    /// the two sides.
+    draw: bool,
+    /// The final score of the battle from the point of view of the winner,
+    /// if the game mode keeps a score.
+    score: Option<MatchScore>,
+    /// The accepted challenge settled by this battle, if the players wagered
+    /// on it.
+    challenge: Option<ChallengeId>,
 }
 
-/// Contract errors
-#[derive(Debug, PartialEq, Eq, Reject, Serial, SchemaType)]
-enum CustomContractError {
-    /// Failed parsing the parameter.
-    #[from(ParseError)]
-    ParseParams,
-    /// Failed logging: Log is full.
-    LogFull,
-    /// Failed logging: Log is malformed.
-    LogMalformed,
-    /// The caller is not the admin.
-    Unauthorized,
-    /// Contract is paused.
-    ContractPaused,
-    /// Failed to invoke a contract.
-    InvokeContractError,
-    /// Player does not exist.
-    PlayerDoesNotExist,
-    /// Upgrade failed because the new module does not exist.
-    FailedUpgradeMissingModule,
-    /// Upgrade failed because the new module does not contain a contract with a
-    /// matching name.
-    FailedUpgradeMissingContract,
-    /// Upgrade failed because the smart contract version of the module is not
-    /// supported.
-    FailedUpgradeUnsupportedModuleVersion,
+/// A battle result signed off-chain by a registered game server.
+#[derive(Serialize, SchemaType)]
+struct SignedResultMessage {
+    /// The contract instance the result is reported to, so that the signature
+    /// cannot be used on other instances.
+    contract_address: ContractAddress,
+    /// The nonce of the game server. It must be the next nonce of the server,
+    /// so that the signature cannot be used twice.
+    nonce: u64,
+    /// The time until which the signed result can be submitted.
+    expiry: Timestamp,
+    /// The result of the battle, as with `reportMatch`.
+    report: ReportMatchParams,
 }
 
-type ContractError = CustomContractError;
-
-type ContractResult<A> = Result<A, CustomContractError>;
+/// The parameter type for the contract function `submitSignedResult`.
+#[derive(Serialize, SchemaType)]
+struct SubmitSignedResultParams {
+    /// The public key of the game server that signed the result.
+    server: PublicKeyEd25519,
+    /// The signature of the serialized message.
+    signature: SignatureEd25519,
+    /// The signed message.
+    message: SignedResultMessage,
+}
 
-/// Mapping the logging errors to ContractError.
-impl From<LogError> for CustomContractError {
-    fn from(le: LogError) -> Self {
-        match le {
-            LogError::Full => Self::LogFull,
-            LogError::Malformed => Self::LogMalformed,
-        }
-    }
+/// A game server that can sign battle results. The nonce is kept when the
+/// server is disabled, so that its old signatures stay unusable.
+#[derive(Debug, Serialize, SchemaType, Clone, Copy, PartialEq, Eq)]
+struct GameServer {
+    /// Whether results signed by the server are accepted.
+    enabled: bool,
+    /// The nonce of the next result signed by the server.
+    nonce: u64,
 }
 
-/// Mapping errors related to contract invocations to CustomContractError.
-impl<T> From<CallContractError<T>> for CustomContractError {
-    fn from(_cce: CallContractError<T>) -> Self { Self::InvokeContractError }
+/// The parameter type for the contract function `setGameServer`.
+#[derive(Debug, Serialize, SchemaType, Clone)]
+struct SetGameServerParams {
+    /// The public key of the game server.
+    public_key: PublicKeyEd25519,
+    /// Whether results signed by the server are accepted.
+    enabled: bool,
 }
 
-/// Mapping errors related to contract upgrades to CustomContractError.
-impl From<UpgradeError> for CustomContractError {
-    #[inline(always)]
-    fn from(ue: UpgradeError) -> Self {
-        match ue {
-            UpgradeError::MissingModule => Self::FailedUpgradeMissingModule,
-            UpgradeError::MissingContract => Self::FailedUpgradeMissingContract,
-            UpgradeError::UnsupportedModuleVersion => Self::FailedUpgradeUnsupportedModuleVersion,
+impl ReportMatchParams {
+    /// Checks that the result names two players and agrees with the score,
+    /// and returns the result for the winner.
+    fn winner_result(&self) -> ContractResult<BattleResult> {
+        ensure!(self.winner != self.loser, ContractError::InvalidMatch);
+        let winner_result = if self.draw {
+            BattleResult::Draw
+        } else {
+            BattleResult::Win
+        };
+        if let Some(score) = self.score {
+            ensure!(score.agrees_with(winner_result), ContractError::InvalidScore);
         }
+        Ok(winner_result)
     }
-}
 
-impl<S: HasStateApi> State<S> {
-    /// Creates the new state of the `state` contract with no one having any
-    /// data by default. The ProtocolAddressesState is uninitialized.
-    /// The ProtocolAddressesState has to be set with the `initialize`
-    /// function after the `proxy` contract is deployed.
-    fn new(state_builder: &mut StateBuilder<S>, admin: Address) -> Self {
-        // Setup state.
-        State {
-            admin,
-            player_data: state_builder.new_map(),
-            paused: false,
-            implementors: state_builder.new_map(),
-        }
+    /// Whether the address is the losing side of the result. Both players
+    /// are the losing side of a draw.
+    fn is_losing_side(&self, address: &Address) -> bool {
+        is_losing_side(&self.winner, &self.loser, self.draw, address)
     }
 
+    /// Returns the reversed result, in which the loser won. A draw cannot be
+    /// reversed.
+    fn overturned(self) -> ContractResult<Self> {
+        ensure!(!self.draw, ContractError::InvalidDecision);
+        Ok(ReportMatchParams {
+            winner: self.loser,
+            loser: self.winner,
+            score: self.score.map(MatchScore::reversed),
+            ..self
+        })
+    }
 }
 
-// Contract functions
-
-/// Init function that creates a new smart contract.
-#[init(contract = "Versus-League-Manager", enable_logger)]
-fn contract_init<S: HasStateApi>(
-    ctx: &impl HasInitContext,
-    state_builder: &mut StateBuilder<S>,
-    logger: &mut impl HasLogger,
-) -> InitResult<State<S>> {
-    // Get the instantiator of this contract instance to be used as the initial
-    // admin.
-    let invoker = Address::Account(ctx.init_origin());
-    // Construct the initial contract state.
-    let state = State::new(state_builder, invoker);
+/// Whether the address is the losing side of a battle. Both players are the
+/// losing side of a draw.
+fn is_losing_side(winner: &Address, loser: &Address, draw: bool, address: &Address) -> bool {
+    *address == *loser || (draw && *address == *winner)
+}
 
-    logger.log(&NewAdminEvent {
-        new_admin: invoker,
-    })?;
+/// The record kept for every reported battle. If an arbitrator overturns the
+/// result, the record names the new winner and loser. A result voided by an
+/// arbitrator keeps its record, so that its battle id cannot be used again.
+#[derive(Debug, Serialize, SchemaType, Clone, PartialEq, Eq)]
+struct BattleRecord {
+    /// The player who won the battle.
+    winner: Address,
+    /// The player who lost the battle.
+    loser: Address,
+    /// The battle ended in a draw.
+    draw: bool,
+    /// The final score of the battle from the point of view of the winner.
+    score: Option<MatchScore>,
+    /// The challenge settled by this battle.
+    challenge: Option<ChallengeId>,
+    /// The time the battle was reported.
+    reported_at: Timestamp,
+    /// The result was voided by an arbitrator and does not count.
+    voided: bool,
+}
 
-    Ok(state)
+/// The status of a result waiting for finalization.
+#[derive(Debug, Serialize, SchemaType, Clone, Copy, PartialEq, Eq)]
+enum PendingResultStatus {
+    /// The result can be confirmed or disputed until the dispute deadline.
+    Proposed,
+    /// The result has been disputed and is not finalized.
+    Disputed,
 }
 
-/// Add new player.
-#[receive(
-    contract = "Versus-League-Manager",
-    name = "setPlayerData",
-    parameter = "(Address, PlayerState)",
-    error = "CustomContractError",
-    mutable,
-)]
-fn contract_state_set_player_data<S: HasStateApi>(
-    ctx: &impl HasReceiveContext,
-    host: &mut impl HasHost<State<S>, StateApiType = S>,
-) -> ContractResult<()> {
-    // Check that contract is not paused.
-    ensure!(!host.state().paused, ContractError::ContractPaused);
-    // Check that only the admin is authorized to set player data.
-    ensure_eq!(
-        ctx.sender(),
-        host.state().admin,
-        ContractError::Unauthorized
-    );
+/// A battle result proposed with `proposeResult`. It changes the players'
+/// data only once it is finalized.
+#[derive(Serialize, SchemaType, Clone)]
+struct PendingResult {
+    /// The proposed result, as with `reportMatch`.
+    report: ReportMatchParams,
+    /// The reporter who proposed the result.
+    proposer: Address,
+    /// The time until which the result can be disputed.
+    dispute_deadline: Timestamp,
+    /// The status of the result.
+    status: PendingResultStatus,
+}
 
-    let params: (Address, PlayerState) = ctx.parameter_cursor().get()?;
+/// The dispute window of a new contract instance, one day.
+const DEFAULT_DISPUTE_WINDOW: Duration = Duration::from_millis(24 * 60 * 60 * 1000);
 
-    host
-        .state_mut()
-        .player_data
-        .entry(params.0)
-        .and_modify(|pd| pd.state = params.1)
-        .or_insert(PlayerData {
-            state: params.1,
-            wins: 0,
-            losses: 0,
-        });
+/// The SHA-256 hash of evidence about a battle that is kept off-chain.
+type EvidenceHash = [u8; 32];
 
-    Ok(())
+/// The parameter type for the contract functions `disputeResult` and
+/// `submitEvidence`.
+#[derive(Serialize, SchemaType)]
+struct DisputeParams {
+    /// The disputed battle.
+    battle_id: BattleId,
+    /// The hash of the evidence of the sender.
+    evidence: EvidenceHash,
 }
 
-#[receive(
-    contract = "Versus-League-Manager",
-    name = "updateBattleResult",
-    parameter = "UpdateBattleResultParams",
-    error = "CustomContractError",
-    mutable,
-    enable_logger
-)]
-fn update_battle_result<S: HasStateApi>(
-    ctx: &impl HasReceiveContext,
-    host: &mut impl HasHost<State<S>, StateApiType = S>,
-    logger: &mut impl HasLogger,
-) -> ContractResult<()> {
-
-    // Check that contract is not paused.
-    ensure!(!host.state().paused, ContractError::ContractPaused);
-    // Check that only the admin is authorized to set player data.
-    ensure_eq!(
-        ctx.sender(),
-        host.state().admin,
-        ContractError::Unauthorized
-    );
-
-    let params: UpdateBattleResultParams = ctx.parameter_cursor().get()?;
+/// The status of a dispute.
+#[derive(Debug, Serialize, SchemaType, Clone, Copy, PartialEq, Eq)]
+enum DisputeStatus {
+    /// The dispute waits for an arbitrator.
+    Open,
+    /// The arbitrator kept the result.
+    Upheld,
+    /// The arbitrator reversed the result.
+    Overturned,
+    /// The arbitrator discarded the result.
+    Voided,
+}
 
-    let player_data = host.state_mut().player_data.get_mut(&params.player);
-    if player_data.is_none() {
-        return Ok(());
-    }
+/// The decision of an arbitrator on a dispute.
+#[derive(Debug, Serialize, SchemaType, Clone, Copy, PartialEq, Eq)]
+enum DisputeDecision {
+    /// Keep the result.
+    Uphold,
+    /// Reverse the result, so that the loser becomes the winner.
+    Overturn,
+    /// Discard the result, as if the battle had not been reported.
+    Void,
+}
 
-    let mut player_data = player_data.unwrap();
+/// A dispute about the result of a battle.
+#[derive(Debug, Serialize, SchemaType, Clone, PartialEq, Eq)]
+struct Dispute {
+    /// The player who won the disputed result.
+    winner: Address,
+    /// The player who lost the disputed result.
+    loser: Address,
+    /// The player who opened the dispute.
+    opened_by: Address,
+    /// The hash of the evidence of the winner, if submitted.
+    winner_evidence: Option<EvidenceHash>,
+    /// The hash of the evidence of the loser, if submitted.
+    loser_evidence: Option<EvidenceHash>,
+    /// The status of the dispute.
+    status: DisputeStatus,
+}
 
-    match params.result {
-        BattleResult::Win => {
-            player_data.wins += 1;
-        }
-        BattleResult::Loss => {
-            player_data.losses += 1;
+impl Dispute {
+    /// Records the evidence of one of the players, replacing earlier evidence.
+    fn set_evidence(&mut self, player: &Address, evidence: EvidenceHash) -> ContractResult<()> {
+        if *player == self.winner {
+            self.winner_evidence = Some(evidence);
+        } else if *player == self.loser {
+            self.loser_evidence = Some(evidence);
+        } else {
+            bail!(ContractError::Unauthorized);
         }
+        Ok(())
     }
+}
 
-    logger.log(&NewBattleResultEvent {
-        player: params.player,
-        is_win: params.result,
-    })?;
+/// The parameter type for the contract function `resolveDispute`.
+#[derive(Serialize, SchemaType)]
+struct ResolveDisputeParams {
+    /// The disputed battle.
+    battle_id: BattleId,
+    /// The decision of the arbitrator.
+    decision: DisputeDecision,
+}
 
-    Ok(())
+/// How much a battle changed the rating of a player.
+#[derive(Serialize, Clone, Copy)]
+struct RatingChange {
+    /// The change of the rating itself.
+    rating: i64,
+    /// The change of the rating deviation.
+    deviation: i64,
+    /// The change of the rating volatility.
+    volatility: i64,
 }
 
-/// Get paused.
-#[receive(
-    contract = "Versus-League-Manager",
-    name = "getPaused",
-    return_value = "bool",
-    error = "CustomContractError"
-)]
-fn contract_state_get_paused<S: HasStateApi>(
-    _ctx: &impl HasReceiveContext,
-    host: &impl HasHost<State<S>, StateApiType = S>,
-) -> ContractResult<bool> {
-    Ok(host.state().paused)
+/// What a reported battle changed in the data of a player that does not
+/// follow from the battle record itself.
+#[derive(Serialize, Clone)]
+struct PlayerChange {
+    /// The change of the rating.
+    rating: RatingChange,
+    /// The number of battles the player had played after the battle.
+    games_played: u64,
+    /// The current streak before the battle.
+    current_streak: i64,
+    /// The longest win streak before the battle.
+    best_win_streak: u64,
+    /// The longest losing streak before the battle.
+    worst_loss_streak: u64,
+    /// The time of the latest battle before the battle.
+    last_played: Option<Timestamp>,
 }
 
-/// Get player data.
-#[receive(
-    contract = "Versus-League-Manager",
-    name = "getPlayerData",
-    parameter = "Address",
-    return_value = "(PlayerState)",
-    error = "CustomContractError"
-)]
-fn contract_state_get_player_data<S: HasStateApi>(
-    ctx: &impl HasReceiveContext,
-    host: &impl HasHost<State<S>, StateApiType = S>,
-) -> ContractResult<PlayerState> {
-    let params: Address = ctx.parameter_cursor().get()?;
+/// What a reported battle changed in the standings of the season it was
+/// played in.
+#[derive(Serialize, Clone)]
+struct SeasonChange {
+    /// The season the battle was played in.
+    season: SeasonId,
+    /// The change of the winner in the season.
+    winner: PlayerChange,
+    /// The change of the loser in the season.
+    loser: PlayerChange,
+}
 
-    let player = host.state().player_data.get(&params);
-    match player {
-        Some(player) => Ok(player.state),
-        None => Err(CustomContractError::PlayerDoesNotExist.into()),
-    }
+/// What a reported battle changed in the data of both players, kept until
+/// the battle can no longer be disputed so that an arbitrator can roll the
+/// battle back.
+#[derive(Serialize, Clone)]
+struct BattleChange {
+    /// The change of the winner.
+    winner: PlayerChange,
+    /// The change of the loser.
+    loser: PlayerChange,
+    /// The change of the season standings, if a season was in progress.
+    season: Option<SeasonChange>,
 }
 
-#[receive(
-    contract = "Versus-League-Manager",
-    name = "isAdded",
-    parameter = "Address",
-    return_value = "bool",
-    error = "CustomContractError"
-)]
-fn contract_state_is_added<S: HasStateApi>(
-    ctx: &impl HasReceiveContext,
-    host: &impl HasHost<State<S>, StateApiType = S>,
-) -> ContractResult<bool> {
-    let params: Address = ctx.parameter_cursor().get()?;
-    let player_data = host.state().player_data.get(&params);
+/// Identifier of a challenge, assigned by the contract.
+type ChallengeId = u64;
 
-    Ok(player_data.is_some())
+/// The status of a challenge.
+#[derive(Debug, Serialize, SchemaType, Clone, Copy, PartialEq, Eq)]
+enum ChallengeStatus {
+    /// The challenger's stake is locked and the opponent can accept.
+    Open,
+    /// Both stakes are locked and the battle can be settled.
+    Accepted,
+    /// The battle was reported and the pot was paid out.
+    Settled,
+    /// The stakes were refunded.
+    Refunded,
 }
 
-/// Function to view state of the state contract.
-#[receive(
-    contract = "Versus-League-Manager",
-    name = "view",
-    return_value = "ReturnBasicState",
-    error = "CustomContractError"
-)]
-fn contract_view<S: HasStateApi>(
-    _ctx: &impl HasReceiveContext,
-    host: &impl HasHost<State<S>, StateApiType = S>,
-) -> ContractResult<ReturnBasicState> {
-    let state = ReturnBasicState {
-        admin: host.state().admin,
-        paused: host.state().paused,
-    };
-    Ok(state)
+/// A CCD wager between two players on their next battle.
+#[derive(Debug, Serialize, SchemaType, Clone, PartialEq, Eq)]
+struct Challenge {
+    /// The player who created the challenge.
+    challenger: AccountAddress,
+    /// The player who was challenged.
+    opponent: AccountAddress,
+    /// The stake locked by each player.
+    stake: Amount,
+    /// The time after which the stakes can be refunded if the challenge has
+    /// not been settled.
+    expiry: Timestamp,
+    /// The status of the challenge.
+    status: ChallengeStatus,
 }
 
-/// Set the admin of the contract instance.
-#[receive(
-    contract = "Versus-League-Manager",
-    name = "updateAdmin",
-    parameter = "Address",
-    error = "ContractError",
-    enable_logger,
-    mutable
-)]
-fn contract_update_admin<S: HasStateApi>(
-    ctx: &impl HasReceiveContext,
-    host: &mut impl HasHost<State<S>, StateApiType = S>,
-    logger: &mut impl HasLogger,
-) -> ContractResult<()> {
-    // Check that only the current admin is authorized to update the admin address.
-    ensure_eq!(ctx.sender(), host.state().admin, ContractError::Unauthorized);
-    
-    // Parse the parameter.
-    let new_admin = ctx.parameter_cursor().get()?;
+/// The parameter type for the contract function `createChallenge`.
+#[derive(Serialize, SchemaType)]
+struct CreateChallengeParams {
+    /// The player being challenged.
+    opponent: AccountAddress,
+    /// The time after which the stakes can be refunded if the challenge has
+    /// not been settled.
+    expiry: Timestamp,
+}
 
-    // Update the admin variable.
-    host.state_mut().admin = new_admin;
+/// Upper bound on fees given in basis points.
+const MAX_BASIS_POINTS: u16 = 10_000;
 
-    logger.log(&NewAdminEvent {
-        new_admin: new_admin,
-    })?;
+/// Identifier of a season. Seasons are numbered from 1, and 0 means that no
+/// season has been started yet.
+type SeasonId = u64;
 
-    Ok(())
+/// The information kept for every season.
+#[derive(Debug, Serialize, SchemaType, Clone, PartialEq, Eq)]
+struct SeasonInfo {
+    /// The time the season was started.
+    started_at: Timestamp,
+    /// The time the season was ended, if it has ended.
+    ended_at: Option<Timestamp>,
+    /// The number of players with at least one result in the season.
+    players: u64,
 }
 
-/// Pause or unpause the contract.
-#[receive(
-    contract = "Versus-League-Manager",
-    name = "setPaused",
-    parameter = "SetPausedParams",
-    error = "ContractError",
-    mutable
-)]
-fn contract_update_pause<S: HasStateApi>(
-    ctx: &impl HasReceiveContext,
-    host: &mut impl HasHost<State<S>, StateApiType = S>,
-) -> ContractResult<()> {
-    // Check that only the admin is authorized to pause/unpause the contract.
-    ensure_eq!(ctx.sender(), host.state().admin, ContractError::Unauthorized);
+/// The parameter type for the contract function `endSeason`.
+#[derive(Serialize, SchemaType)]
+struct EndSeasonParams {
+    /// Start the next season right away.
+    start_next: bool,
+    /// The prizes to pay out for the ended season.
+    payouts: Vec<PrizePayout>,
+}
 
-    // Parse the parameter.
-    let params: SetPausedParams = ctx.parameter_cursor().get()?;
+/// The token type used for prize pools.
+type ContractTokenId = TokenIdVec;
 
-    // Update the paused variable.
-    host.state_mut().paused = params.paused;
+/// The token amount type used for prize pools. CIS-2 token contracts with
+/// amounts that do not fit in a `u64` are not supported.
+type ContractTokenAmount = TokenAmountU64;
 
-    Ok(())
+/// Identifies the balance of one token in one prize pool.
+#[derive(Debug, Serialize, SchemaType, Clone, PartialEq, Eq)]
+struct PrizePoolKey {
+    /// The name of the prize pool.
+    pool: String,
+    /// The CIS-2 contract of the token.
+    token_contract: ContractAddress,
+    /// The id of the token.
+    token_id: ContractTokenId,
 }
 
-/// Upgrade this smart contract instance to a new module and call optionally a
-/// migration function after the upgrade.
-///
-/// It rejects if:
-/// - Sender is not the admin of the contract instance.
-/// - It fails to parse the parameter.
-/// - If the ugrade fails.
-/// - If the migration invoke fails.
-///
-/// This function is marked as `low_level`. This is **necessary** since the
-/// high-level mutable functions store the state of the contract at the end of
-/// execution. This conflicts with migration since the shape of the state
-/// **might** be changed by the migration function. If the state is then written
-/// by this function it would overwrite the state stored by the migration
-/// function.
-#[receive(
-    contract = "Versus-League-Manager",
-    name = "upgrade",
-    parameter = "UpgradeParams",
-    error = "CustomContractError",
-    low_level
-)]
-fn contract_upgrade<S: HasStateApi>(
-    ctx: &impl HasReceiveContext,
-    host: &mut impl HasHost<S>,
-) -> ContractResult<()> {
-    // Read the top-level contract state.
-    let state: State<S> = host.state().read_root()?;
+/// A prize paid out from a prize pool, used by `payoutPrize` and
+/// `endSeason`.
+#[derive(Debug, Serialize, SchemaType, Clone)]
+struct PrizePayout {
+    /// The prize pool and token to pay out from.
+    key: PrizePoolKey,
+    /// The amount of tokens to pay out.
+    amount: ContractTokenAmount,
+    /// The receiver of the prize.
+    to: Receiver,
+}
 
-    // Check that only the admin is authorized to upgrade the smart contract.
-    ensure_eq!(ctx.sender(), state.admin, ContractError::Unauthorized);
-    // Parse the parameter.
-    let params: UpgradeParams = ctx.parameter_cursor().get()?;
-    // Trigger the upgrade.
-    host.upgrade(params.module)?;
-    // Call the migration function if provided.
-    if let Some((func, parameters)) = params.migrate {
-        host.invoke_contract_raw(
-            &ctx.self_address(),
-            parameters.as_parameter(),
-            func.as_entrypoint_name(),
-            Amount::zero(),
-        )?;
+/// The parameter type for the contract function `setPrizeToken`.
+#[derive(Serialize, SchemaType)]
+struct SetPrizeTokenParams {
+    /// The CIS-2 token contract.
+    token_contract: ContractAddress,
+    /// Whether tokens from the contract can be deposited into prize pools.
+    allowed: bool,
+}
+
+/// The parameter type for the contract function `setImplementors`.
+#[derive(Debug, Serialize, SchemaType)]
+struct SetImplementorsParams {
+    /// The identifier for the standard.
+    id: StandardIdentifierOwned,
+    /// The addresses of the implementors of the standard.
+    implementors: Vec<ContractAddress>,
+}
+
+/// The standards this contract supports itself.
+const SUPPORTS_STANDARDS: [StandardIdentifier<'static>; 1] = [CIS0_STANDARD_IDENTIFIER];
+
+/// The contract state.
+#[derive(Serial, DeserialWithState, StateClone)]
+#[concordium(state_parameter = "S")]
+struct State<S: HasStateApi> {
+    /// The version of the state layout, always `STATE_VERSION`. Older layouts
+    /// are converted with `migrate`.
+    version: StateVersion,
+    /// The admin address can upgrade the contract, pause and unpause the
+    /// contract, transfer the admin address to a new address, set
+    /// implementors, and update the metadata URL in the contract.
+    admin: Address,
+    /// The admin address proposed with `proposeAdmin`, which becomes the
+    /// admin once it accepts.
+    pending_admin: Option<PendingAdmin>,
+    /// The upgrade scheduled with `scheduleUpgrade`.
+    pending_upgrade: Option<UpgradeParams>,
+    /// The multisig that replaces the admin for sensitive actions, if
+    /// enabled.
+    multisig: Option<MultisigConfig>,
+    /// The multisig proposals.
+    proposals: StateMap<ProposalId, Proposal, S>,
+    /// The identifier of the next proposal.
+    next_proposal_id: ProposalId,
+    /// The roles granted to each address.
+    roles: StateSet<(Address, Role), S>,
+    /// The game servers that can sign battle results, by public key.
+    game_servers: StateMap<PublicKeyEd25519, GameServer, S>,
+    /// The state of the one player.
+    player_data: StateMap<Address, PlayerData, S>,
+    /// The number of players in `player_data`.
+    player_count: u64,
+    /// The timed suspensions of players. A player whose suspension has ended
+    /// is reinstated the next time the player takes part in a battle or
+    /// challenge.
+    suspensions: StateMap<Address, Suspension, S>,
+    /// The battles reported with `reportMatch`, used to reject repeated
+    /// reports of the same battle.
+    battles: StateMap<BattleId, BattleRecord, S>,
+    /// The results proposed with `proposeResult` that are not finalized, by
+    /// battle.
+    pending_results: StateMap<BattleId, PendingResult, S>,
+    /// How long the losing side can dispute a result after it was proposed
+    /// or reported.
+    dispute_window: Duration,
+    /// What each reported battle changed in the data of the players, used to
+    /// roll back battles that are overturned or voided.
+    battle_changes: StateMap<BattleId, BattleChange, S>,
+    /// The disputes about battle results, by battle.
+    disputes: StateMap<BattleId, Dispute, S>,
+    /// The latest season that has been started.
+    current_season: SeasonId,
+    /// The information of every season that has been started.
+    seasons: StateMap<SeasonId, SeasonInfo, S>,
+    /// The stats of each player within a season. Stats of ended seasons are
+    /// kept as archived standings.
+    season_player_data: StateMap<(SeasonId, Address), PlayerData, S>,
+    /// Contract is paused/unpaused.
+    paused: bool,
+    /// The rating engine used to update the players' ratings.
+    rating_engine: RatingEngineConfig,
+    /// The settings for players registering themselves.
+    registration: RegistrationConfig,
+    /// The CCD collected from entry fees that the admin can withdraw.
+    treasury: Amount,
+    /// The challenges between players, with their locked stakes.
+    challenges: StateMap<ChallengeId, Challenge, S>,
+    /// The identifier of the next challenge.
+    next_challenge_id: ChallengeId,
+    /// The fee on the pot of a settled challenge in basis points, added to
+    /// the treasury.
+    wager_fee: u16,
+    /// The CIS-2 token contracts whose tokens can be deposited into prize
+    /// pools.
+    prize_tokens: StateSet<ContractAddress, S>,
+    /// The token balances of the prize pools.
+    prize_pools: StateMap<PrizePoolKey, ContractTokenAmount, S>,
+    /// The settings of the leaderboard.
+    leaderboard_config: LeaderboardConfig,
+    /// The best players sorted by rank, updated with every result. Kept in
+    /// its own box so that it is only loaded when it is read or updated.
+    leaderboard: StateBox<Vec<LeaderboardEntry>, S>,
+    /// The streak lengths that log a `StreakMilestone` event when a player
+    /// reaches them.
+    streak_milestones: Vec<u64>,
+    /// How strictly player updates and battle results are validated.
+    validation_mode: ValidationMode,
+    /// Map with contract addresses providing implementations of additional
+    /// standards.
+    implementors: StateMap<StandardIdentifierOwned, Vec<ContractAddress>, S>,
+}
+
+/// The version of the state layout written by this module. Increase it and
+/// add a step to `migrate_state` whenever the layout of `State` or
+/// `PlayerData` changes.
+const STATE_VERSION: u32 = 1;
+
+/// The first byte of the state root of every versioned layout. The root of the
+/// unversioned layout, version 0, starts with the tag of the admin `Address`,
+/// which is never this byte.
+const STATE_VERSION_MARKER: u8 = 0xff;
+
+/// The version of the state layout, serialized as `STATE_VERSION_MARKER`
+/// followed by the version number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct StateVersion(u32);
+
+impl Serial for StateVersion {
+    fn serial<W: Write>(&self, out: &mut W) -> Result<(), W::Err> {
+        out.write_u8(STATE_VERSION_MARKER)?;
+        self.0.serial(out)
     }
-    Ok(())
 }
 
-#[concordium_cfg_test]
-mod tests {
-    use super::*;
-    use test_infrastructure::*;
+impl Deserial for StateVersion {
+    fn deserial<R: Read>(source: &mut R) -> ParseResult<Self> {
+        ensure_eq!(source.read_u8()?, STATE_VERSION_MARKER, ParseError::default());
+        Ok(StateVersion(source.read_u32()?))
+    }
+}
+
+/// The contract state of version 0, the layout before the state was
+/// versioned.
+#[derive(Serial, DeserialWithState)]
+#[concordium(state_parameter = "S")]
+struct StateV0<S: HasStateApi> {
+    admin: Address,
+    player_data: StateMap<Address, PlayerDataV0, S>,
+    paused: bool,
+    implementors: StateMap<StandardIdentifierOwned, Vec<ContractAddress>, S>,
+}
+
+/// The state tracked for each address in state version 0.
+#[derive(Serialize, Clone)]
+struct PlayerDataV0 {
+    state: PlayerState,
+    wins: u64,
+    losses: u64,
+}
+
+#[derive(Debug, Serialize, SchemaType, Clone, Copy, PartialEq)]
+enum PlayerState {
+    Active,
+    Suspended,
+    /// The player registered and waits for a moderator to approve it.
+    PendingApproval,
+}
+
+impl PlayerState {
+    /// Checks whether a moderator may move a player from state `from` to
+    /// state `to`, where `from` is `None` for a player that is being added.
+    /// Players can only wait for approval right after being added, and
+    /// setting a player to the state it already has is not a transition.
+    fn is_valid_transition(from: Option<PlayerState>, to: PlayerState) -> bool {
+        match (from, to) {
+            (None, PlayerState::Suspended) => false,
+            (None, _) => true,
+            (Some(_), PlayerState::PendingApproval) => false,
+            (Some(from), to) => from != to,
+        }
+    }
+}
+
+/// How strictly player updates and battle results are validated.
+#[derive(Debug, Serialize, SchemaType, Clone, Copy, PartialEq, Eq)]
+enum ValidationMode {
+    /// Results for players that have not been added are ignored and any
+    /// state can be set with `setPlayerData`.
+    Lenient,
+    /// Results for players that have not been added are rejected and
+    /// `setPlayerData` only accepts valid state transitions.
+    Strict,
+}
+
+/// A suspension of a player that ends at a given time.
+#[derive(Debug, Serialize, SchemaType, Clone, Copy, PartialEq, Eq)]
+struct Suspension {
+    /// The time the player is reinstated.
+    until: Timestamp,
+    /// The code of the reason for the suspension.
+    reason: u16,
+    /// The moderator who suspended the player.
+    moderator: Address,
+    /// The state the player is restored to once the suspension ends.
+    previous_state: PlayerState,
+}
+
+/// The parameter type for the contract function `suspendPlayer`.
+#[derive(Serialize, SchemaType)]
+struct SuspendPlayerParams {
+    /// The player to suspend.
+    player: Address,
+    /// The time the player is reinstated.
+    until: Timestamp,
+    /// The code of the reason for the suspension.
+    reason: u16,
+}
+
+/// The return type for the contract function `getSuspension`.
+#[derive(Serialize, SchemaType)]
+struct SuspensionStatus {
+    /// The suspension of the player.
+    suspension: Suspension,
+    /// The time left until the player is reinstated.
+    remaining: Duration,
+}
+
+/// The fee for registering with `register`.
+#[derive(Debug, Serialize, SchemaType, Clone, Copy, PartialEq, Eq)]
+enum RegistrationFee {
+    /// Registration is free.
+    Free,
+    /// Registration costs a fixed amount of CCD.
+    Fixed(Amount),
+    /// Registration is closed.
+    Closed,
+}
+
+/// The settings for players registering themselves with `register`.
+#[derive(Debug, Serialize, SchemaType, Clone, Copy, PartialEq, Eq)]
+struct RegistrationConfig {
+    /// The entry fee.
+    fee: RegistrationFee,
+    /// New players start as `PendingApproval` instead of `Active` and need a
+    /// moderator to activate them with `setPlayerData`. Until then they
+    /// cannot record battles.
+    requires_approval: bool,
+}
+
+/// The stat players are ranked by on the leaderboard.
+#[derive(Debug, Serialize, SchemaType, Clone, Copy, PartialEq, Eq)]
+enum LeaderboardRanking {
+    /// Rank by lifetime rating.
+    Rating,
+    /// Rank by lifetime wins.
+    Wins,
+}
+
+impl LeaderboardRanking {
+    /// The value a player is ranked by, higher is better.
+    fn key(self, player_data: &PlayerData) -> u64 {
+        match self {
+            LeaderboardRanking::Rating => player_data.rating.rating,
+            LeaderboardRanking::Wins => player_data.wins,
+        }
+    }
+}
+
+/// The settings of the leaderboard.
+#[derive(Debug, Serialize, SchemaType, Clone, Copy, PartialEq, Eq)]
+struct LeaderboardConfig {
+    /// The stat players are ranked by.
+    ranking: LeaderboardRanking,
+    /// The number of players kept on the leaderboard.
+    size: u32,
+}
+
+/// Upper bound on the number of players kept on the leaderboard.
+const MAX_LEADERBOARD_SIZE: u32 = 100;
+
+/// A player on the leaderboard with the value the player is ranked by.
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+struct LeaderboardEntry {
+    /// The player.
+    player: Address,
+    /// The value of the ranked stat when the player was last ranked.
+    key: u64,
+}
+
+/// A position on the leaderboard as returned by `getLeaderboard`.
+#[derive(Serialize, SchemaType)]
+struct LeaderboardPosition {
+    /// The rank of the player, starting from 1.
+    rank: u32,
+    /// The player.
+    player: Address,
+    /// The lifetime data of the player.
+    stats: PlayerData,
+}
+
+/// The parameter type for the contract function `getPlayers`.
+#[derive(Serialize, SchemaType)]
+struct GetPlayersParams {
+    /// The player to continue after, `None` for the first page and otherwise
+    /// the `next_cursor` of the previous page.
+    cursor: Option<Address>,
+    /// The maximum number of players to return, at least 1 and capped at
+    /// `MAX_PLAYERS_PAGE_SIZE`.
+    limit: u32,
+    /// Only return players in this state.
+    state: Option<PlayerState>,
+}
+
+/// Upper bound on the number of players returned by `getPlayers`.
+const MAX_PLAYERS_PAGE_SIZE: u32 = 100;
+
+/// A player as returned by `getPlayers`.
+#[derive(Serialize, SchemaType)]
+struct PlayerEntry {
+    /// The address of the player.
+    player: Address,
+    /// The lifetime data of the player.
+    data: PlayerData,
+}
+
+/// The return type for the contract function `getPlayers`.
+#[derive(Serialize, SchemaType)]
+struct PlayersPage {
+    /// The players on this page.
+    players: Vec<PlayerEntry>,
+    /// The cursor of the next page, or `None` if this is the last page.
+    next_cursor: Option<Address>,
+    /// The number of players added to the contract.
+    player_count: u64,
+}
+
+/// The kind of a streak.
+#[derive(Debug, Serialize, SchemaType, Clone, Copy, PartialEq, Eq)]
+enum StreakKind {
+    /// Battles won in a row.
+    Win,
+    /// Battles lost in a row.
+    Loss,
+}
+
+/// Upper bound on the number of streak milestones.
+const MAX_STREAK_MILESTONES: usize = 16;
+
+/// The parameter type for the contract function `withdrawTreasury`.
+#[derive(Debug, Serialize, SchemaType, Clone)]
+struct WithdrawTreasuryParams {
+    /// The account receiving the CCD.
+    to: AccountAddress,
+    /// The amount to withdraw.
+    amount: Amount,
+}
+
+#[derive(Debug, Serialize, SchemaType, Clone, Copy)]
+enum BattleResult {
+    Win,
+    Loss,
+    Draw,
+}
+
+impl BattleResult {
+    /// The result from the point of view of the opponent.
+    fn reversed(self) -> Self {
+        match self {
+            BattleResult::Win => BattleResult::Loss,
+            BattleResult::Loss => BattleResult::Win,
+            BattleResult::Draw => BattleResult::Draw,
+        }
+    }
+
+    /// The fixed-point score of the player for this result.
+    fn score(self) -> i128 {
+        match self {
+            BattleResult::Win => RATING_SCALE,
+            BattleResult::Loss => 0,
+            BattleResult::Draw => RATING_SCALE / 2,
+        }
+    }
+}
+
+/// The final score of a battle from the point of view of one player, e.g.
+/// 3-2.
+#[derive(Debug, Serialize, SchemaType, Clone, Copy, PartialEq, Eq)]
+struct MatchScore {
+    /// The points scored by the player.
+    score_for: u64,
+    /// The points scored by the opponent.
+    score_against: u64,
+}
+
+impl MatchScore {
+    /// The score from the point of view of the opponent.
+    fn reversed(self) -> Self {
+        MatchScore {
+            score_for: self.score_against,
+            score_against: self.score_for,
+        }
+    }
+
+    /// Checks that the score agrees with the result of the battle.
+    fn agrees_with(&self, result: BattleResult) -> bool {
+        match result {
+            BattleResult::Win => self.score_for > self.score_against,
+            BattleResult::Loss => self.score_for < self.score_against,
+            BattleResult::Draw => self.score_for == self.score_against,
+        }
+    }
+}
+
+/// Elo rating engine.
+#[derive(Debug, Serialize, SchemaType, Clone, Copy, PartialEq, Eq)]
+struct Elo {
+    /// The maximum rating change of a single battle, in whole rating points.
+    k_factor: u64,
+}
+
+/// Glicko-2 rating engine. Every battle is treated as its own rating period.
+#[derive(Debug, Serialize, SchemaType, Clone, Copy, PartialEq, Eq)]
+struct Glicko2 {
+    /// The system constant tau as a fixed-point number. It constrains the
+    /// change in volatility over time.
+    tau: u64,
+}
+
+/// The rating engine chosen by the admin.
+#[derive(Debug, Serialize, SchemaType, Clone, Copy, PartialEq, Eq)]
+enum RatingEngineConfig {
+    Elo(Elo),
+    Glicko2(Glicko2),
+}
+
+/// An admin address proposed with `proposeAdmin` that has not accepted yet.
+#[derive(Debug, Serialize, SchemaType, Clone, Copy, PartialEq, Eq)]
+struct PendingAdmin {
+    /// The proposed admin address.
+    address: Address,
+    /// The time after which the proposal can no longer be accepted, if any.
+    expiry: Option<Timestamp>,
+}
+
+/// The parameter type for the contract function `proposeAdmin`.
+#[derive(Debug, Serialize, SchemaType, Clone)]
+struct ProposeAdminParams {
+    /// The proposed admin address.
+    new_admin: Address,
+    /// The time after which the proposal can no longer be accepted, if any.
+    expiry: Option<Timestamp>,
+}
+
+/// The parameter type for the contract function `scheduleUpgrade`, and the
+/// upgrade kept until it is executed.
+#[derive(Debug, Serialize, SchemaType, Clone)]
+struct UpgradeParams {
+    /// The new module reference.
+    module: ModuleReference,
+    /// Optional entrypoint to call in the new module after upgrade.
+    migrate: Option<(OwnedEntrypointName, OwnedParameter)>,
+    /// The time from which the upgrade can be executed. It must be at least
+    /// `UPGRADE_DELAY` after the upgrade is scheduled.
+    execute_after: Timestamp,
+}
+
+/// The minimum time between scheduling and executing an upgrade, giving
+/// players and partners two days of warning.
+const UPGRADE_DELAY: Duration = Duration::from_millis(2 * 24 * 60 * 60 * 1000);
+
+/// The signers of the multisig and the number of approvals a proposal needs.
+#[derive(Debug, Serialize, SchemaType, Clone, PartialEq, Eq)]
+struct MultisigConfig {
+    /// The accounts that can submit and approve proposals.
+    signers: Vec<AccountAddress>,
+    /// The number of approvals a proposal needs to run.
+    threshold: u32,
+}
+
+/// Upper bound on the number of multisig signers.
+const MAX_MULTISIG_SIGNERS: usize = 20;
+
+impl MultisigConfig {
+    /// Checks that the signers are distinct and that the threshold can be
+    /// reached.
+    fn is_valid(&self) -> bool {
+        let mut signers = self.signers.clone();
+        signers.sort();
+        signers.dedup();
+        signers.len() == self.signers.len()
+            && signers.len() <= MAX_MULTISIG_SIGNERS
+            && self.threshold > 0
+            && self.threshold as usize <= signers.len()
+    }
+}
+
+/// Identifier of a multisig proposal, assigned by the contract.
+type ProposalId = u64;
+
+/// A sensitive admin action that runs once the multisig approves it.
+#[derive(Debug, Serialize, SchemaType, Clone)]
+enum ProposalAction {
+    /// Schedule an upgrade, as with `scheduleUpgrade`.
+    ScheduleUpgrade(UpgradeParams),
+    /// Cancel the scheduled upgrade, as with `cancelUpgrade`.
+    CancelUpgrade,
+    /// Pause or unpause the contract.
+    SetPaused(bool),
+    /// Propose a new admin, who still has to accept with `acceptAdmin`.
+    ProposeAdmin(ProposeAdminParams),
+    /// Withdraw CCD from the treasury, as with `withdrawTreasury`.
+    WithdrawTreasury(WithdrawTreasuryParams),
+    /// Change the signers and threshold, or turn the multisig off.
+    SetMultisig(Option<MultisigConfig>),
+    /// Pay out a prize from a prize pool, as with `payoutPrize`.
+    PayoutPrize(PrizePayout),
+    /// Enable or disable a game server, as with `setGameServer`.
+    SetGameServer(SetGameServerParams),
+    /// Grant a role to an address, as with `grantRole`.
+    GrantRole(RoleParams),
+    /// Cancel the proposed admin transfer, as with `cancelAdminTransfer`.
+    CancelAdminTransfer,
+}
+
+/// A proposal waiting for the approvals of the multisig signers.
+#[derive(Debug, Serialize, SchemaType, Clone)]
+struct Proposal {
+    /// The action to run.
+    action: ProposalAction,
+    /// The signer who submitted the proposal.
+    proposer: AccountAddress,
+    /// The signers who approved the proposal, including the proposer.
+    approvals: Vec<AccountAddress>,
+    /// The time after which the proposal can no longer be approved.
+    expiry: Timestamp,
+    /// Whether the proposal has run.
+    executed: bool,
+}
+
+/// The parameter type for the contract function `submitProposal`.
+#[derive(Serialize, SchemaType)]
+struct SubmitProposalParams {
+    /// The action to run.
+    action: ProposalAction,
+    /// The time after which the proposal can no longer be approved.
+    expiry: Timestamp,
+}
+
+/// The return type for the contract function `view`.
+#[derive(Serialize, SchemaType)]
+struct ReturnBasicState {
+    /// The version of the state layout.
+    version: u32,
+    /// The admin address can upgrade the contract, pause and unpause the
+    /// contract, transfer the admin address to a new address, set
+    /// implementors, and update the metadata URL in the contract.
+    admin: Address,
+    /// The admin address proposed with `proposeAdmin`, if any.
+    pending_admin: Option<PendingAdmin>,
+    /// The multisig that replaces the admin for sensitive actions, if
+    /// enabled.
+    multisig: Option<MultisigConfig>,
+    /// Contract is paused if `paused = true` and unpaused if `paused = false`.
+    paused: bool,
+    /// The rating engine used to update the players' ratings.
+    rating_engine: RatingEngineConfig,
+    /// The latest season that has been started.
+    current_season: SeasonId,
+    /// The settings for players registering themselves.
+    registration: RegistrationConfig,
+    /// The CCD collected from entry fees that the admin can withdraw.
+    treasury: Amount,
+    /// The fee on the pot of a settled challenge in basis points.
+    wager_fee: u16,
+    /// The settings of the leaderboard.
+    leaderboard: LeaderboardConfig,
+    /// The streak lengths that log a `StreakMilestone` event.
+    streak_milestones: Vec<u64>,
+    /// How strictly player updates and battle results are validated.
+    validation_mode: ValidationMode,
+    /// How long the losing side can dispute a result after it was proposed
+    /// or reported.
+    dispute_window: Duration,
+}
+
+/// The parameter type for the contract function `setPaused`.
+#[derive(Serialize, SchemaType)]
+#[repr(transparent)]
+struct SetPausedParams {
+    /// Contract is paused if `paused = true` and unpaused if `paused = false`.
+    paused: bool,
+}
+
+/// Tag for the NewAdmin event.
+const NEW_ADMIN_EVENT_TAG: u8 = 0;
+
+/// Tag for the PlayerRegistered event.
+const PLAYER_REGISTERED_EVENT_TAG: u8 = 1;
+
+/// Tag for the PlayerStateChanged event.
+const PLAYER_STATE_CHANGED_EVENT_TAG: u8 = 2;
+
+/// Tag for the BattleResult event.
+const BATTLE_RESULT_EVENT_TAG: u8 = 3;
+
+/// Tag for the MatchReported event.
+const MATCH_REPORTED_EVENT_TAG: u8 = 4;
+
+/// Tag for the Paused event.
+const PAUSED_EVENT_TAG: u8 = 5;
+
+/// Tag for the Upgrade event.
+const UPGRADE_EVENT_TAG: u8 = 6;
+
+/// Tag for the GrantRole event.
+const GRANT_ROLE_EVENT_TAG: u8 = 7;
+
+/// Tag for the RevokeRole event.
+const REVOKE_ROLE_EVENT_TAG: u8 = 8;
+
+/// Tag for the SeasonStarted event.
+const SEASON_STARTED_EVENT_TAG: u8 = 9;
+
+/// Tag for the SeasonEnded event.
+const SEASON_ENDED_EVENT_TAG: u8 = 10;
+
+/// Tag for the RatingEngine event.
+const RATING_ENGINE_EVENT_TAG: u8 = 11;
+
+/// Tag for the Registration event.
+const REGISTRATION_EVENT_TAG: u8 = 12;
+
+/// Tag for the TreasuryWithdrawal event.
+const TREASURY_WITHDRAWAL_EVENT_TAG: u8 = 13;
+
+/// Tag for the ChallengeCreated event.
+const CHALLENGE_CREATED_EVENT_TAG: u8 = 14;
+
+/// Tag for the ChallengeAccepted event.
+const CHALLENGE_ACCEPTED_EVENT_TAG: u8 = 15;
+
+/// Tag for the ChallengeSettled event.
+const CHALLENGE_SETTLED_EVENT_TAG: u8 = 16;
+
+/// Tag for the ChallengeRefunded event.
+const CHALLENGE_REFUNDED_EVENT_TAG: u8 = 17;
+
+/// Tag for the WagerFee event.
+const WAGER_FEE_EVENT_TAG: u8 = 18;
+
+/// Tag for the PrizeDeposited event.
+const PRIZE_DEPOSITED_EVENT_TAG: u8 = 19;
+
+/// Tag for the PrizePaid event.
+const PRIZE_PAID_EVENT_TAG: u8 = 20;
+
+/// Tag for the PrizeToken event.
+const PRIZE_TOKEN_EVENT_TAG: u8 = 21;
+
+/// Tag for the Leaderboard event.
+const LEADERBOARD_EVENT_TAG: u8 = 22;
+
+/// Tag for the StreakMilestone event.
+const STREAK_MILESTONE_EVENT_TAG: u8 = 23;
+
+/// Tag for the StreakMilestones event.
+const STREAK_MILESTONES_EVENT_TAG: u8 = 24;
+
+/// Tag for the PlayerSuspended event.
+const PLAYER_SUSPENDED_EVENT_TAG: u8 = 25;
+
+/// Tag for the ValidationMode event.
+const VALIDATION_MODE_EVENT_TAG: u8 = 26;
+
+/// Tag for the AdminProposed event.
+const ADMIN_PROPOSED_EVENT_TAG: u8 = 27;
+
+/// Tag for the AdminTransferCancelled event.
+const ADMIN_TRANSFER_CANCELLED_EVENT_TAG: u8 = 28;
+
+/// Tag for the ProposalSubmitted event.
+const PROPOSAL_SUBMITTED_EVENT_TAG: u8 = 29;
+
+/// Tag for the ProposalApproved event.
+const PROPOSAL_APPROVED_EVENT_TAG: u8 = 30;
+
+/// Tag for the ProposalExecuted event.
+const PROPOSAL_EXECUTED_EVENT_TAG: u8 = 31;
+
+/// Tag for the Multisig event.
+const MULTISIG_EVENT_TAG: u8 = 32;
+
+/// Tag for the UpgradeScheduled event.
+const UPGRADE_SCHEDULED_EVENT_TAG: u8 = 33;
+
+/// Tag for the UpgradeCancelled event.
+const UPGRADE_CANCELLED_EVENT_TAG: u8 = 34;
+
+/// Tag for the StateMigrated event.
+const STATE_MIGRATED_EVENT_TAG: u8 = 35;
+
+/// Tag for the GameServer event.
+const GAME_SERVER_EVENT_TAG: u8 = 36;
+
+/// Tag for the SignedResult event.
+const SIGNED_RESULT_EVENT_TAG: u8 = 37;
+
+/// Tag for the ResultProposed event.
+const RESULT_PROPOSED_EVENT_TAG: u8 = 38;
+
+/// Tag for the ResultConfirmed event.
+const RESULT_CONFIRMED_EVENT_TAG: u8 = 39;
+
+/// Tag for the ResultDisputed event.
+const RESULT_DISPUTED_EVENT_TAG: u8 = 40;
+
+/// Tag for the ResultFinalized event.
+const RESULT_FINALIZED_EVENT_TAG: u8 = 41;
+
+/// Tag for the DisputeWindow event.
+const DISPUTE_WINDOW_EVENT_TAG: u8 = 42;
+
+/// Tag for the EvidenceSubmitted event.
+const EVIDENCE_SUBMITTED_EVENT_TAG: u8 = 43;
+
+/// Tag for the DisputeResolved event.
+const DISPUTE_RESOLVED_EVENT_TAG: u8 = 44;
+
+/// Tag for the Implementors event.
+const IMPLEMENTORS_EVENT_TAG: u8 = 45;
+
+/// Tagged events to be serialized for the event log. Every state change of
+/// the contract is logged as one of these events, prefixed with a stable
+/// one-byte tag in the same way as CIS-2 events.
+enum VersusEvent {
+    /// The admin address was changed.
+    NewAdmin(NewAdminEvent),
+    /// A player was added.
+    PlayerRegistered(PlayerRegisteredEvent),
+    /// The state of an existing player was changed.
+    PlayerStateChanged(PlayerStateChangedEvent),
+    /// The result of one side of a battle was recorded.
+    BattleResult(BattleResultEvent),
+    /// Both sides of a battle were recorded.
+    MatchReported(MatchReportedEvent),
+    /// The contract was paused or unpaused.
+    Paused(PausedEvent),
+    /// The contract was upgraded to a new module.
+    Upgrade(UpgradeEvent),
+    /// A role was granted to an address.
+    GrantRole(GrantRoleEvent),
+    /// A role was revoked from an address.
+    RevokeRole(RevokeRoleEvent),
+    /// A season was started.
+    SeasonStarted(SeasonStartedEvent),
+    /// A season was ended.
+    SeasonEnded(SeasonEndedEvent),
+    /// The rating engine was changed.
+    RatingEngine(RatingEngineEvent),
+    /// The registration settings were changed.
+    Registration(RegistrationEvent),
+    /// CCD was withdrawn from the treasury.
+    TreasuryWithdrawal(TreasuryWithdrawalEvent),
+    /// A challenge was created.
+    ChallengeCreated(ChallengeCreatedEvent),
+    /// A challenge was accepted.
+    ChallengeAccepted(ChallengeAcceptedEvent),
+    /// The pot of a challenge was paid out.
+    ChallengeSettled(ChallengeSettledEvent),
+    /// The stakes of a challenge were refunded.
+    ChallengeRefunded(ChallengeRefundedEvent),
+    /// The wager fee was changed.
+    WagerFee(WagerFeeEvent),
+    /// Tokens were deposited into a prize pool.
+    PrizeDeposited(PrizeDepositedEvent),
+    /// Tokens were paid out from a prize pool.
+    PrizePaid(PrizePaidEvent),
+    /// A token contract was added to or removed from the prize token
+    /// allowlist.
+    PrizeToken(PrizeTokenEvent),
+    /// The leaderboard settings were changed.
+    Leaderboard(LeaderboardEvent),
+    /// A player reached a streak milestone.
+    StreakMilestone(StreakMilestoneEvent),
+    /// The streak milestones were changed.
+    StreakMilestones(StreakMilestonesEvent),
+    /// A player was suspended until a given time.
+    PlayerSuspended(PlayerSuspendedEvent),
+    /// The validation mode was changed.
+    ValidationMode(ValidationModeEvent),
+    /// A new admin was proposed.
+    AdminProposed(AdminProposedEvent),
+    /// The proposed admin transfer was cancelled.
+    AdminTransferCancelled(AdminTransferCancelledEvent),
+    /// A multisig proposal was submitted.
+    ProposalSubmitted(ProposalSubmittedEvent),
+    /// A signer approved a multisig proposal.
+    ProposalApproved(ProposalApprovedEvent),
+    /// A multisig proposal ran.
+    ProposalExecuted(ProposalExecutedEvent),
+    /// The multisig was changed.
+    Multisig(MultisigEvent),
+    /// An upgrade was scheduled.
+    UpgradeScheduled(UpgradeScheduledEvent),
+    /// The scheduled upgrade was cancelled.
+    UpgradeCancelled(UpgradeCancelledEvent),
+    /// The state was migrated to a newer layout.
+    StateMigrated(StateMigratedEvent),
+    /// A game server was enabled or disabled.
+    GameServer(GameServerEvent),
+    /// A battle result signed by a game server was submitted.
+    SignedResult(SignedResultEvent),
+    /// A battle result was proposed.
+    ResultProposed(ResultProposedEvent),
+    /// The losing side confirmed a proposed result.
+    ResultConfirmed(ResultConfirmedEvent),
+    /// The losing side disputed a result.
+    ResultDisputed(ResultDisputedEvent),
+    /// A proposed result was finalized.
+    ResultFinalized(ResultFinalizedEvent),
+    /// The dispute window was updated.
+    DisputeWindow(DisputeWindowEvent),
+    /// A player submitted evidence for a dispute.
+    EvidenceSubmitted(EvidenceSubmittedEvent),
+    /// An arbitrator resolved a dispute.
+    DisputeResolved(DisputeResolvedEvent),
+    /// The implementors of a standard were updated.
+    Implementors(ImplementorsEvent),
+}
+
+/// A NewAdminEvent introduced by this smart contract.
+#[derive(Serial, SchemaType)]
+#[repr(transparent)]
+struct NewAdminEvent {
+    /// New admin address.
+    new_admin: Address,
+}
+
+/// A PlayerRegisteredEvent introduced by this smart contract.
+#[derive(Serial, SchemaType)]
+struct PlayerRegisteredEvent {
+    /// Player address.
+    player: Address,
+    /// The state the player was added with.
+    state: PlayerState,
+}
+
+/// A PlayerStateChangedEvent introduced by this smart contract.
+#[derive(Serial, SchemaType)]
+struct PlayerStateChangedEvent {
+    /// Player address.
+    player: Address,
+    /// The player's new state.
+    state: PlayerState,
+}
+
+/// A BattleResultEvent introduced by this smart contract.
+/// This event is emitted when a player's battle result is updated.
+#[derive(Serial, SchemaType)]
+struct BattleResultEvent {
+    /// Player address.
+    player: Address,
+    /// Player's new battle result.
+    result: BattleResult,
+    /// The final score of the battle from the point of view of the player.
+    score: Option<MatchScore>,
+    /// Player's rating after the battle.
+    rating: Rating,
+}
+
+/// A MatchReportedEvent introduced by this smart contract.
+/// This event is emitted when both results of a battle are recorded.
+#[derive(Serial, SchemaType)]
+struct MatchReportedEvent {
+    /// Unique identifier of the battle.
+    battle_id: BattleId,
+    /// The player who won the battle.
+    winner: Address,
+    /// The player who lost the battle.
+    loser: Address,
+    /// The battle ended in a draw.
+    draw: bool,
+    /// The final score of the battle from the point of view of the winner.
+    score: Option<MatchScore>,
+    /// The winner's rating after the battle.
+    winner_rating: Rating,
+    /// The loser's rating after the battle.
+    loser_rating: Rating,
+}
+
+/// A PausedEvent introduced by this smart contract.
+#[derive(Serial, SchemaType)]
+#[repr(transparent)]
+struct PausedEvent {
+    /// Contract is paused if `paused = true` and unpaused if `paused = false`.
+    paused: bool,
+}
+
+/// An UpgradeEvent introduced by this smart contract.
+#[derive(Serial, SchemaType)]
+#[repr(transparent)]
+struct UpgradeEvent {
+    /// The new module reference.
+    module: ModuleReference,
+}
+
+/// A GrantRoleEvent introduced by this smart contract.
+#[derive(Serial, SchemaType)]
+struct GrantRoleEvent {
+    /// The address that was granted the role.
+    address: Address,
+    /// The role that was granted.
+    role: Role,
+}
+
+/// A RevokeRoleEvent introduced by this smart contract.
+#[derive(Serial, SchemaType)]
+struct RevokeRoleEvent {
+    /// The address that the role was revoked from.
+    address: Address,
+    /// The role that was revoked.
+    role: Role,
+}
+
+/// A SeasonStartedEvent introduced by this smart contract.
+#[derive(Serial, SchemaType)]
+#[repr(transparent)]
+struct SeasonStartedEvent {
+    /// The season that was started.
+    season: SeasonId,
+}
+
+/// A SeasonEndedEvent introduced by this smart contract.
+#[derive(Serial, SchemaType)]
+struct SeasonEndedEvent {
+    /// The season that was ended.
+    season: SeasonId,
+    /// The number of players with at least one result in the season.
+    players: u64,
+}
+
+/// A RatingEngineEvent introduced by this smart contract.
+#[derive(Serial, SchemaType)]
+#[repr(transparent)]
+struct RatingEngineEvent {
+    /// The new rating engine.
+    engine: RatingEngineConfig,
+}
+
+/// A RegistrationEvent introduced by this smart contract.
+#[derive(Serial, SchemaType)]
+#[repr(transparent)]
+struct RegistrationEvent {
+    /// The new registration settings.
+    config: RegistrationConfig,
+}
+
+/// A TreasuryWithdrawalEvent introduced by this smart contract.
+#[derive(Serial, SchemaType)]
+struct TreasuryWithdrawalEvent {
+    /// The account receiving the CCD.
+    to: AccountAddress,
+    /// The amount withdrawn.
+    amount: Amount,
+}
+
+/// A ChallengeCreatedEvent introduced by this smart contract.
+#[derive(Serial, SchemaType)]
+struct ChallengeCreatedEvent {
+    /// The new challenge.
+    challenge: ChallengeId,
+    /// The player who created the challenge.
+    challenger: AccountAddress,
+    /// The player who was challenged.
+    opponent: AccountAddress,
+    /// The stake locked by each player.
+    stake: Amount,
+    /// The time after which the stakes can be refunded.
+    expiry: Timestamp,
+}
+
+/// A ChallengeAcceptedEvent introduced by this smart contract.
+#[derive(Serial, SchemaType)]
+#[repr(transparent)]
+struct ChallengeAcceptedEvent {
+    /// The accepted challenge.
+    challenge: ChallengeId,
+}
+
+/// A ChallengeSettledEvent introduced by this smart contract.
+#[derive(Serial, SchemaType)]
+struct ChallengeSettledEvent {
+    /// The settled challenge.
+    challenge: ChallengeId,
+    /// The battle that settled the challenge.
+    battle_id: BattleId,
+    /// The player who received the pot, or `None` for a draw, in which case
+    /// both stakes were refunded.
+    winner: Option<AccountAddress>,
+    /// The amount paid to the winner.
+    payout: Amount,
+    /// The fee added to the treasury.
+    fee: Amount,
+}
+
+/// A ChallengeRefundedEvent introduced by this smart contract.
+#[derive(Serial, SchemaType)]
+#[repr(transparent)]
+struct ChallengeRefundedEvent {
+    /// The refunded challenge.
+    challenge: ChallengeId,
+}
+
+/// A WagerFeeEvent introduced by this smart contract.
+#[derive(Serial, SchemaType)]
+#[repr(transparent)]
+struct WagerFeeEvent {
+    /// The new wager fee in basis points.
+    wager_fee: u16,
+}
+
+/// A PrizeDepositedEvent introduced by this smart contract.
+#[derive(Serial, SchemaType)]
+struct PrizeDepositedEvent {
+    /// The prize pool and token deposited.
+    key: PrizePoolKey,
+    /// The amount deposited.
+    amount: ContractTokenAmount,
+    /// The previous owner of the tokens.
+    from: Address,
+}
+
+/// A PrizePaidEvent introduced by this smart contract.
+#[derive(Serial, SchemaType)]
+struct PrizePaidEvent {
+    /// The prize pool and token paid out.
+    key: PrizePoolKey,
+    /// The amount paid out.
+    amount: ContractTokenAmount,
+    /// The receiver of the prize.
+    to: Address,
+}
+
+/// A PrizeTokenEvent introduced by this smart contract.
+#[derive(Serial, SchemaType)]
+struct PrizeTokenEvent {
+    /// The CIS-2 token contract.
+    token_contract: ContractAddress,
+    /// Whether tokens from the contract can be deposited into prize pools.
+    allowed: bool,
+}
+
+/// A LeaderboardEvent introduced by this smart contract.
+#[derive(Serial, SchemaType)]
+#[repr(transparent)]
+struct LeaderboardEvent {
+    /// The new leaderboard settings.
+    config: LeaderboardConfig,
+}
+
+/// A StreakMilestoneEvent introduced by this smart contract.
+#[derive(Serial, SchemaType)]
+struct StreakMilestoneEvent {
+    /// The player on the streak.
+    player: Address,
+    /// The kind of the streak.
+    kind: StreakKind,
+    /// The length of the streak.
+    streak: u64,
+}
+
+/// A StreakMilestonesEvent introduced by this smart contract.
+#[derive(Serial, SchemaType)]
+#[repr(transparent)]
+struct StreakMilestonesEvent {
+    /// The new streak milestones.
+    milestones: Vec<u64>,
+}
+
+/// A PlayerSuspendedEvent introduced by this smart contract.
+#[derive(Serial, SchemaType)]
+struct PlayerSuspendedEvent {
+    /// The suspended player.
+    player: Address,
+    /// The time the player is reinstated.
+    until: Timestamp,
+    /// The code of the reason for the suspension.
+    reason: u16,
+    /// The moderator who suspended the player.
+    moderator: Address,
+}
+
+/// A ValidationModeEvent introduced by this smart contract.
+#[derive(Serial, SchemaType)]
+#[repr(transparent)]
+struct ValidationModeEvent {
+    /// The new validation mode.
+    mode: ValidationMode,
+}
+
+/// An AdminProposedEvent introduced by this smart contract.
+#[derive(Serial, SchemaType)]
+struct AdminProposedEvent {
+    /// The proposed admin address.
+    new_admin: Address,
+    /// The time after which the proposal can no longer be accepted, if any.
+    expiry: Option<Timestamp>,
+}
+
+/// An AdminTransferCancelledEvent introduced by this smart contract.
+#[derive(Serial, SchemaType)]
+#[repr(transparent)]
+struct AdminTransferCancelledEvent {
+    /// The admin address that was proposed.
+    pending_admin: Address,
+}
+
+/// A ProposalSubmittedEvent introduced by this smart contract. The action is
+/// not logged since an upgrade parameter can exceed the size of an event;
+/// use `getProposal` to read it.
+#[derive(Serial, SchemaType)]
+struct ProposalSubmittedEvent {
+    /// The new proposal.
+    proposal: ProposalId,
+    /// The signer who submitted the proposal.
+    proposer: AccountAddress,
+    /// The time after which the proposal can no longer be approved.
+    expiry: Timestamp,
+}
+
+/// A ProposalApprovedEvent introduced by this smart contract.
+#[derive(Serial, SchemaType)]
+struct ProposalApprovedEvent {
+    /// The approved proposal.
+    proposal: ProposalId,
+    /// The signer who approved.
+    signer: AccountAddress,
+    /// The number of approvals of current signers.
+    approvals: u32,
+}
+
+/// A ProposalExecutedEvent introduced by this smart contract.
+#[derive(Serial, SchemaType)]
+#[repr(transparent)]
+struct ProposalExecutedEvent {
+    /// The proposal that ran.
+    proposal: ProposalId,
+}
+
+/// A MultisigEvent introduced by this smart contract.
+#[derive(Serial, SchemaType)]
+#[repr(transparent)]
+struct MultisigEvent {
+    /// The new multisig, or `None` if it was turned off.
+    config: Option<MultisigConfig>,
+}
+
+/// An UpgradeScheduledEvent introduced by this smart contract.
+#[derive(Serial, SchemaType)]
+struct UpgradeScheduledEvent {
+    /// The new module reference.
+    module: ModuleReference,
+    /// The time from which the upgrade can be executed.
+    execute_after: Timestamp,
+}
+
+/// An UpgradeCancelledEvent introduced by this smart contract.
+#[derive(Serial, SchemaType)]
+#[repr(transparent)]
+struct UpgradeCancelledEvent {
+    /// The module reference of the cancelled upgrade.
+    module: ModuleReference,
+}
+
+/// A StateMigratedEvent introduced by this smart contract.
+#[derive(Serial, SchemaType)]
+struct StateMigratedEvent {
+    /// The version of the state layout before the migration.
+    from: u32,
+    /// The version of the state layout after the migration.
+    to: u32,
+}
+
+/// A GameServerEvent introduced by this smart contract.
+#[derive(Serial, SchemaType)]
+struct GameServerEvent {
+    /// The public key of the game server.
+    public_key: PublicKeyEd25519,
+    /// Whether results signed by the server are accepted.
+    enabled: bool,
+}
+
+/// A SignedResultEvent introduced by this smart contract.
+#[derive(Serial, SchemaType)]
+struct SignedResultEvent {
+    /// The public key of the game server that signed the result.
+    server: PublicKeyEd25519,
+    /// The nonce used by the signed result.
+    nonce: u64,
+    /// Unique identifier of the battle.
+    battle_id: BattleId,
+}
+
+/// A ResultProposedEvent introduced by this smart contract.
+#[derive(Serial, SchemaType)]
+struct ResultProposedEvent {
+    /// Unique identifier of the battle.
+    battle_id: BattleId,
+    /// The player who won the battle.
+    winner: Address,
+    /// The player who lost the battle.
+    loser: Address,
+    /// The battle ended in a draw.
+    draw: bool,
+    /// The time until which the result can be disputed.
+    dispute_deadline: Timestamp,
+}
+
+/// A ResultConfirmedEvent introduced by this smart contract.
+#[derive(Serial, SchemaType)]
+struct ResultConfirmedEvent {
+    /// Unique identifier of the battle.
+    battle_id: BattleId,
+    /// The player who confirmed the result.
+    player: Address,
+}
+
+/// A ResultDisputedEvent introduced by this smart contract.
+#[derive(Serial, SchemaType)]
+struct ResultDisputedEvent {
+    /// Unique identifier of the battle.
+    battle_id: BattleId,
+    /// The player who disputed the result.
+    player: Address,
+    /// The hash of the evidence of the player.
+    evidence: EvidenceHash,
+}
+
+/// A ResultFinalizedEvent introduced by this smart contract.
+#[derive(Serial, SchemaType)]
+#[repr(transparent)]
+struct ResultFinalizedEvent {
+    /// Unique identifier of the battle.
+    battle_id: BattleId,
+}
+
+/// A DisputeWindowEvent introduced by this smart contract.
+#[derive(Serial, SchemaType)]
+#[repr(transparent)]
+struct DisputeWindowEvent {
+    /// The new dispute window.
+    dispute_window: Duration,
+}
+
+/// An EvidenceSubmittedEvent introduced by this smart contract.
+#[derive(Serial, SchemaType)]
+struct EvidenceSubmittedEvent {
+    /// Unique identifier of the battle.
+    battle_id: BattleId,
+    /// The player who submitted the evidence.
+    player: Address,
+    /// The hash of the evidence.
+    evidence: EvidenceHash,
+}
+
+/// A DisputeResolvedEvent introduced by this smart contract.
+#[derive(Serial, SchemaType)]
+struct DisputeResolvedEvent {
+    /// Unique identifier of the battle.
+    battle_id: BattleId,
+    /// The arbitrator who resolved the dispute.
+    arbitrator: Address,
+    /// The decision of the arbitrator.
+    decision: DisputeDecision,
+}
+
+/// An ImplementorsEvent introduced by this smart contract.
+#[derive(Serial, SchemaType)]
+struct ImplementorsEvent {
+    /// The identifier for the standard.
+    id: StandardIdentifierOwned,
+    /// The addresses of the implementors of the standard, empty if the
+    /// standard was removed.
+    implementors: Vec<ContractAddress>,
+}
+
+impl Serial for VersusEvent {
+    fn serial<W: Write>(&self, out: &mut W) -> Result<(), W::Err> {
+        match self {
+            VersusEvent::NewAdmin(event) => {
+                out.write_u8(NEW_ADMIN_EVENT_TAG)?;
+                event.serial(out)
+            }
+            VersusEvent::PlayerRegistered(event) => {
+                out.write_u8(PLAYER_REGISTERED_EVENT_TAG)?;
+                event.serial(out)
+            }
+            VersusEvent::PlayerStateChanged(event) => {
+                out.write_u8(PLAYER_STATE_CHANGED_EVENT_TAG)?;
+                event.serial(out)
+            }
+            VersusEvent::BattleResult(event) => {
+                out.write_u8(BATTLE_RESULT_EVENT_TAG)?;
+                event.serial(out)
+            }
+            VersusEvent::MatchReported(event) => {
+                out.write_u8(MATCH_REPORTED_EVENT_TAG)?;
+                event.serial(out)
+            }
+            VersusEvent::Paused(event) => {
+                out.write_u8(PAUSED_EVENT_TAG)?;
+                event.serial(out)
+            }
+            VersusEvent::Upgrade(event) => {
+                out.write_u8(UPGRADE_EVENT_TAG)?;
+                event.serial(out)
+            }
+            VersusEvent::GrantRole(event) => {
+                out.write_u8(GRANT_ROLE_EVENT_TAG)?;
+                event.serial(out)
+            }
+            VersusEvent::RevokeRole(event) => {
+                out.write_u8(REVOKE_ROLE_EVENT_TAG)?;
+                event.serial(out)
+            }
+            VersusEvent::SeasonStarted(event) => {
+                out.write_u8(SEASON_STARTED_EVENT_TAG)?;
+                event.serial(out)
+            }
+            VersusEvent::SeasonEnded(event) => {
+                out.write_u8(SEASON_ENDED_EVENT_TAG)?;
+                event.serial(out)
+            }
+            VersusEvent::RatingEngine(event) => {
+                out.write_u8(RATING_ENGINE_EVENT_TAG)?;
+                event.serial(out)
+            }
+            VersusEvent::Registration(event) => {
+                out.write_u8(REGISTRATION_EVENT_TAG)?;
+                event.serial(out)
+            }
+            VersusEvent::TreasuryWithdrawal(event) => {
+                out.write_u8(TREASURY_WITHDRAWAL_EVENT_TAG)?;
+                event.serial(out)
+            }
+            VersusEvent::ChallengeCreated(event) => {
+                out.write_u8(CHALLENGE_CREATED_EVENT_TAG)?;
+                event.serial(out)
+            }
+            VersusEvent::ChallengeAccepted(event) => {
+                out.write_u8(CHALLENGE_ACCEPTED_EVENT_TAG)?;
+                event.serial(out)
+            }
+            VersusEvent::ChallengeSettled(event) => {
+                out.write_u8(CHALLENGE_SETTLED_EVENT_TAG)?;
+                event.serial(out)
+            }
+            VersusEvent::ChallengeRefunded(event) => {
+                out.write_u8(CHALLENGE_REFUNDED_EVENT_TAG)?;
+                event.serial(out)
+            }
+            VersusEvent::WagerFee(event) => {
+                out.write_u8(WAGER_FEE_EVENT_TAG)?;
+                event.serial(out)
+            }
+            VersusEvent::PrizeDeposited(event) => {
+                out.write_u8(PRIZE_DEPOSITED_EVENT_TAG)?;
+                event.serial(out)
+            }
+            VersusEvent::PrizePaid(event) => {
+                out.write_u8(PRIZE_PAID_EVENT_TAG)?;
+                event.serial(out)
+            }
+            VersusEvent::PrizeToken(event) => {
+                out.write_u8(PRIZE_TOKEN_EVENT_TAG)?;
+                event.serial(out)
+            }
+            VersusEvent::Leaderboard(event) => {
+                out.write_u8(LEADERBOARD_EVENT_TAG)?;
+                event.serial(out)
+            }
+            VersusEvent::StreakMilestone(event) => {
+                out.write_u8(STREAK_MILESTONE_EVENT_TAG)?;
+                event.serial(out)
+            }
+            VersusEvent::StreakMilestones(event) => {
+                out.write_u8(STREAK_MILESTONES_EVENT_TAG)?;
+                event.serial(out)
+            }
+            VersusEvent::PlayerSuspended(event) => {
+                out.write_u8(PLAYER_SUSPENDED_EVENT_TAG)?;
+                event.serial(out)
+            }
+            VersusEvent::ValidationMode(event) => {
+                out.write_u8(VALIDATION_MODE_EVENT_TAG)?;
+                event.serial(out)
+            }
+            VersusEvent::AdminProposed(event) => {
+                out.write_u8(ADMIN_PROPOSED_EVENT_TAG)?;
+                event.serial(out)
+            }
+            VersusEvent::AdminTransferCancelled(event) => {
+                out.write_u8(ADMIN_TRANSFER_CANCELLED_EVENT_TAG)?;
+                event.serial(out)
+            }
+            VersusEvent::ProposalSubmitted(event) => {
+                out.write_u8(PROPOSAL_SUBMITTED_EVENT_TAG)?;
+                event.serial(out)
+            }
+            VersusEvent::ProposalApproved(event) => {
+                out.write_u8(PROPOSAL_APPROVED_EVENT_TAG)?;
+                event.serial(out)
+            }
+            VersusEvent::ProposalExecuted(event) => {
+                out.write_u8(PROPOSAL_EXECUTED_EVENT_TAG)?;
+                event.serial(out)
+            }
+            VersusEvent::Multisig(event) => {
+                out.write_u8(MULTISIG_EVENT_TAG)?;
+                event.serial(out)
+            }
+            VersusEvent::UpgradeScheduled(event) => {
+                out.write_u8(UPGRADE_SCHEDULED_EVENT_TAG)?;
+                event.serial(out)
+            }
+            VersusEvent::UpgradeCancelled(event) => {
+                out.write_u8(UPGRADE_CANCELLED_EVENT_TAG)?;
+                event.serial(out)
+            }
+            VersusEvent::StateMigrated(event) => {
+                out.write_u8(STATE_MIGRATED_EVENT_TAG)?;
+                event.serial(out)
+            }
+            VersusEvent::GameServer(event) => {
+                out.write_u8(GAME_SERVER_EVENT_TAG)?;
+                event.serial(out)
+            }
+            VersusEvent::SignedResult(event) => {
+                out.write_u8(SIGNED_RESULT_EVENT_TAG)?;
+                event.serial(out)
+            }
+            VersusEvent::ResultProposed(event) => {
+                out.write_u8(RESULT_PROPOSED_EVENT_TAG)?;
+                event.serial(out)
+            }
+            VersusEvent::ResultConfirmed(event) => {
+                out.write_u8(RESULT_CONFIRMED_EVENT_TAG)?;
+                event.serial(out)
+            }
+            VersusEvent::ResultDisputed(event) => {
+                out.write_u8(RESULT_DISPUTED_EVENT_TAG)?;
+                event.serial(out)
+            }
+            VersusEvent::ResultFinalized(event) => {
+                out.write_u8(RESULT_FINALIZED_EVENT_TAG)?;
+                event.serial(out)
+            }
+            VersusEvent::DisputeWindow(event) => {
+                out.write_u8(DISPUTE_WINDOW_EVENT_TAG)?;
+                event.serial(out)
+            }
+            VersusEvent::EvidenceSubmitted(event) => {
+                out.write_u8(EVIDENCE_SUBMITTED_EVENT_TAG)?;
+                event.serial(out)
+            }
+            VersusEvent::DisputeResolved(event) => {
+                out.write_u8(DISPUTE_RESOLVED_EVENT_TAG)?;
+                event.serial(out)
+            }
+            VersusEvent::Implementors(event) => {
+                out.write_u8(IMPLEMENTORS_EVENT_TAG)?;
+                event.serial(out)
+            }
+        }
+    }
+}
+
+/// Returns the schema of the fields of an event struct.
+fn event_fields<T: SchemaType>() -> schema::Fields {
+    match T::get_type() {
+        schema::Type::Struct(fields) => fields,
+        _ => schema::Fields::None,
+    }
+}
+
+impl schema::SchemaType for VersusEvent {
+    fn get_type() -> schema::Type {
+        let mut event_map = BTreeMap::new();
+        event_map.insert(
+            NEW_ADMIN_EVENT_TAG,
+            ("NewAdmin".to_string(), event_fields::<NewAdminEvent>()),
+        );
+        event_map.insert(
+            PLAYER_REGISTERED_EVENT_TAG,
+            ("PlayerRegistered".to_string(), event_fields::<PlayerRegisteredEvent>()),
+        );
+        event_map.insert(
+            PLAYER_STATE_CHANGED_EVENT_TAG,
+            ("PlayerStateChanged".to_string(), event_fields::<PlayerStateChangedEvent>()),
+        );
+        event_map.insert(
+            BATTLE_RESULT_EVENT_TAG,
+            ("BattleResult".to_string(), event_fields::<BattleResultEvent>()),
+        );
+        event_map.insert(
+            MATCH_REPORTED_EVENT_TAG,
+            ("MatchReported".to_string(), event_fields::<MatchReportedEvent>()),
+        );
+        event_map.insert(PAUSED_EVENT_TAG, ("Paused".to_string(), event_fields::<PausedEvent>()));
+        event_map
+            .insert(UPGRADE_EVENT_TAG, ("Upgrade".to_string(), event_fields::<UpgradeEvent>()));
+        event_map.insert(
+            GRANT_ROLE_EVENT_TAG,
+            ("GrantRole".to_string(), event_fields::<GrantRoleEvent>()),
+        );
+        event_map.insert(
+            REVOKE_ROLE_EVENT_TAG,
+            ("RevokeRole".to_string(), event_fields::<RevokeRoleEvent>()),
+        );
+        event_map.insert(
+            SEASON_STARTED_EVENT_TAG,
+            ("SeasonStarted".to_string(), event_fields::<SeasonStartedEvent>()),
+        );
+        event_map.insert(
+            SEASON_ENDED_EVENT_TAG,
+            ("SeasonEnded".to_string(), event_fields::<SeasonEndedEvent>()),
+        );
+        event_map.insert(
+            RATING_ENGINE_EVENT_TAG,
+            ("RatingEngine".to_string(), event_fields::<RatingEngineEvent>()),
+        );
+        event_map.insert(
+            REGISTRATION_EVENT_TAG,
+            ("Registration".to_string(), event_fields::<RegistrationEvent>()),
+        );
+        event_map.insert(
+            TREASURY_WITHDRAWAL_EVENT_TAG,
+            ("TreasuryWithdrawal".to_string(), event_fields::<TreasuryWithdrawalEvent>()),
+        );
+        event_map.insert(
+            CHALLENGE_CREATED_EVENT_TAG,
+            ("ChallengeCreated".to_string(), event_fields::<ChallengeCreatedEvent>()),
+        );
+        event_map.insert(
+            CHALLENGE_ACCEPTED_EVENT_TAG,
+            ("ChallengeAccepted".to_string(), event_fields::<ChallengeAcceptedEvent>()),
+        );
+        event_map.insert(
+            CHALLENGE_SETTLED_EVENT_TAG,
+            ("ChallengeSettled".to_string(), event_fields::<ChallengeSettledEvent>()),
+        );
+        event_map.insert(
+            CHALLENGE_REFUNDED_EVENT_TAG,
+            ("ChallengeRefunded".to_string(), event_fields::<ChallengeRefundedEvent>()),
+        );
+        event_map.insert(
+            WAGER_FEE_EVENT_TAG,
+            ("WagerFee".to_string(), event_fields::<WagerFeeEvent>()),
+        );
+        event_map.insert(
+            PRIZE_DEPOSITED_EVENT_TAG,
+            ("PrizeDeposited".to_string(), event_fields::<PrizeDepositedEvent>()),
+        );
+        event_map.insert(
+            PRIZE_PAID_EVENT_TAG,
+            ("PrizePaid".to_string(), event_fields::<PrizePaidEvent>()),
+        );
+        event_map.insert(
+            PRIZE_TOKEN_EVENT_TAG,
+            ("PrizeToken".to_string(), event_fields::<PrizeTokenEvent>()),
+        );
+        event_map.insert(
+            LEADERBOARD_EVENT_TAG,
+            ("Leaderboard".to_string(), event_fields::<LeaderboardEvent>()),
+        );
+        event_map.insert(
+            STREAK_MILESTONE_EVENT_TAG,
+            ("StreakMilestone".to_string(), event_fields::<StreakMilestoneEvent>()),
+        );
+        event_map.insert(
+            STREAK_MILESTONES_EVENT_TAG,
+            ("StreakMilestones".to_string(), event_fields::<StreakMilestonesEvent>()),
+        );
+        event_map.insert(
+            PLAYER_SUSPENDED_EVENT_TAG,
+            ("PlayerSuspended".to_string(), event_fields::<PlayerSuspendedEvent>()),
+        );
+        event_map.insert(
+            VALIDATION_MODE_EVENT_TAG,
+            ("ValidationMode".to_string(), event_fields::<ValidationModeEvent>()),
+        );
+        event_map.insert(
+            ADMIN_PROPOSED_EVENT_TAG,
+            ("AdminProposed".to_string(), event_fields::<AdminProposedEvent>()),
+        );
+        event_map.insert(
+            ADMIN_TRANSFER_CANCELLED_EVENT_TAG,
+            (
+                "AdminTransferCancelled".to_string(),
+                event_fields::<AdminTransferCancelledEvent>(),
+            ),
+        );
+        event_map.insert(
+            PROPOSAL_SUBMITTED_EVENT_TAG,
+            ("ProposalSubmitted".to_string(), event_fields::<ProposalSubmittedEvent>()),
+        );
+        event_map.insert(
+            PROPOSAL_APPROVED_EVENT_TAG,
+            ("ProposalApproved".to_string(), event_fields::<ProposalApprovedEvent>()),
+        );
+        event_map.insert(
+            PROPOSAL_EXECUTED_EVENT_TAG,
+            ("ProposalExecuted".to_string(), event_fields::<ProposalExecutedEvent>()),
+        );
+        event_map.insert(
+            MULTISIG_EVENT_TAG,
+            ("Multisig".to_string(), event_fields::<MultisigEvent>()),
+        );
+        event_map.insert(
+            UPGRADE_SCHEDULED_EVENT_TAG,
+            ("UpgradeScheduled".to_string(), event_fields::<UpgradeScheduledEvent>()),
+        );
+        event_map.insert(
+            UPGRADE_CANCELLED_EVENT_TAG,
+            ("UpgradeCancelled".to_string(), event_fields::<UpgradeCancelledEvent>()),
+        );
+        event_map.insert(
+            STATE_MIGRATED_EVENT_TAG,
+            ("StateMigrated".to_string(), event_fields::<StateMigratedEvent>()),
+        );
+        event_map.insert(
+            GAME_SERVER_EVENT_TAG,
+            ("GameServer".to_string(), event_fields::<GameServerEvent>()),
+        );
+        event_map.insert(
+            SIGNED_RESULT_EVENT_TAG,
+            ("SignedResult".to_string(), event_fields::<SignedResultEvent>()),
+        );
+        event_map.insert(
+            RESULT_PROPOSED_EVENT_TAG,
+            ("ResultProposed".to_string(), event_fields::<ResultProposedEvent>()),
+        );
+        event_map.insert(
+            RESULT_CONFIRMED_EVENT_TAG,
+            ("ResultConfirmed".to_string(), event_fields::<ResultConfirmedEvent>()),
+        );
+        event_map.insert(
+            RESULT_DISPUTED_EVENT_TAG,
+            ("ResultDisputed".to_string(), event_fields::<ResultDisputedEvent>()),
+        );
+        event_map.insert(
+            RESULT_FINALIZED_EVENT_TAG,
+            ("ResultFinalized".to_string(), event_fields::<ResultFinalizedEvent>()),
+        );
+        event_map.insert(
+            DISPUTE_WINDOW_EVENT_TAG,
+            ("DisputeWindow".to_string(), event_fields::<DisputeWindowEvent>()),
+        );
+        event_map.insert(
+            EVIDENCE_SUBMITTED_EVENT_TAG,
+            ("EvidenceSubmitted".to_string(), event_fields::<EvidenceSubmittedEvent>()),
+        );
+        event_map.insert(
+            DISPUTE_RESOLVED_EVENT_TAG,
+            ("DisputeResolved".to_string(), event_fields::<DisputeResolvedEvent>()),
+        );
+        event_map.insert(
+            IMPLEMENTORS_EVENT_TAG,
+            ("Implementors".to_string(), event_fields::<ImplementorsEvent>()),
+        );
+        schema::Type::TaggedEnum(event_map)
+    }
+}
+
+/// Contract errors
+#[derive(Debug, PartialEq, Eq, Reject, Serial, SchemaType)]
+enum CustomContractError {
+    /// Failed parsing the parameter.
+    #[from(ParseError)]
+    ParseParams,
+    /// Failed logging: Log is full.
+    LogFull,
+    /// Failed logging: Log is malformed.
+    LogMalformed,
+    /// The caller is not the admin or does not have the required role.
+    Unauthorized,
+    /// Contract is paused.
+    ContractPaused,
+    /// Failed to invoke a contract.
+    InvokeContractError,
+    /// Player does not exist.
+    PlayerDoesNotExist,
+    /// Upgrade failed because the new module does not exist.
+    FailedUpgradeMissingModule,
+    /// Upgrade failed because the new module does not contain a contract with a
+    /// matching name.
+    FailedUpgradeMissingContract,
+    /// Upgrade failed because the smart contract version of the module is not
+    /// supported.
+    FailedUpgradeUnsupportedModuleVersion,
+    /// The rating engine configuration is invalid.
+    InvalidRatingEngine,
+    /// A battle with the same identifier has already been reported.
+    BattleAlreadyReported,
+    /// Battle does not exist.
+    BattleDoesNotExist,
+    /// The winner and the loser of a battle are the same player.
+    InvalidMatch,
+    /// The score of a battle does not agree with its result.
+    InvalidScore,
+    /// Failed to transfer CCD.
+    InvokeTransferError,
+    /// Player has already been added.
+    PlayerAlreadyExists,
+    /// Registration is closed.
+    RegistrationClosed,
+    /// The transferred amount does not match the expected amount.
+    WrongAmount,
+    /// Only accounts can call this function.
+    OnlyAccount,
+    /// The treasury holds less than the requested amount.
+    InsufficientFunds,
+    /// Player is not active, e.g. suspended or waiting for approval.
+    PlayerNotActive,
+    /// Challenge does not exist.
+    ChallengeDoesNotExist,
+    /// The challenge does not have the status required for this action.
+    InvalidChallengeStatus,
+    /// The challenge has expired.
+    ChallengeExpired,
+    /// The challenge has not expired yet.
+    ChallengeNotExpired,
+    /// The players of the battle are not the players of the challenge.
+    ChallengeMismatch,
+    /// The expiry is not in the future.
+    InvalidExpiry,
+    /// The fee is larger than 100%.
+    InvalidFee,
+    /// The token contract is not on the prize token allowlist.
+    TokenNotAllowed,
+    /// The leaderboard size is zero or too large.
+    InvalidLeaderboardSize,
+    /// The streak milestones are not increasing, contain zero or are too
+    /// many.
+    InvalidStreakMilestones,
+    /// Player is suspended.
+    PlayerSuspended,
+    /// The player cannot be moved to the requested state.
+    InvalidStateTransition,
+    /// No admin transfer has been proposed.
+    NoPendingAdmin,
+    /// The proposed admin transfer has expired.
+    AdminTransferExpired,
+    /// The action has to be approved by the multisig.
+    MultisigRequired,
+    /// The multisig is not enabled.
+    MultisigNotEnabled,
+    /// The signers are not distinct, too many, or the threshold cannot be
+    /// reached.
+    InvalidMultisig,
+    /// Proposal does not exist.
+    ProposalDoesNotExist,
+    /// The proposal has expired.
+    ProposalExpired,
+    /// The proposal has already run.
+    ProposalAlreadyExecuted,
+    /// The signer has already approved the proposal.
+    AlreadyApproved,
+    /// The upgrade is scheduled earlier than `UPGRADE_DELAY` from now.
+    UpgradeTooEarly,
+    /// The scheduled upgrade cannot be executed yet.
+    UpgradeNotReady,
+    /// No upgrade has been scheduled.
+    NoPendingUpgrade,
+    /// The state already has the layout of this module.
+    StateAlreadyMigrated,
+    /// The state has a layout this module cannot migrate.
+    UnknownStateVersion,
+    /// The game server is not registered or has been disabled.
+    UnknownGameServer,
+    /// The signed message is for another contract instance.
+    WrongContract,
+    /// The nonce is not the next nonce of the game server.
+    NonceMismatch,
+    /// The signed message has expired.
+    SignatureExpired,
+    /// The signature does not match the message and the public key.
+    WrongSignature,
+    /// A result for the battle has already been proposed.
+    ResultAlreadyProposed,
+    /// No result has been proposed for the battle.
+    PendingResultDoesNotExist,
+    /// The proposed result does not have the required status.
+    InvalidResultStatus,
+    /// The dispute window of the proposed result has closed.
+    DisputeWindowClosed,
+    /// The dispute window of the proposed result is still open.
+    DisputeWindowOpen,
+    /// The result of the battle has already been disputed.
+    AlreadyDisputed,
+    /// The battle has not been disputed.
+    DisputeDoesNotExist,
+    /// The dispute does not have the required status.
+    InvalidDisputeStatus,
+    /// The decision cannot be applied to the result, e.g. overturning a draw.
+    InvalidDecision,
+    /// A season is already in progress.
+    SeasonInProgress,
+    /// No season is in progress.
+    NoActiveSeason,
+    /// Season does not exist.
+    SeasonDoesNotExist,
+    /// The deposit would overflow the balance of the prize pool.
+    PrizePoolOverflow,
+    /// The battle settled a challenge, whose payouts cannot be reversed.
+    ChallengeSettled,
+    /// The page size is zero.
+    InvalidPageSize,
+}
+
+type ContractError = CustomContractError;
+
+type ContractResult<A> = Result<A, CustomContractError>;
+
+/// Mapping the logging errors to ContractError.
+impl From<LogError> for CustomContractError {
+    fn from(le: LogError) -> Self {
+        match le {
+            LogError::Full => Self::LogFull,
+            LogError::Malformed => Self::LogMalformed,
+        }
+    }
+}
+
+/// Mapping errors related to contract invocations to CustomContractError.
+impl<T> From<CallContractError<T>> for CustomContractError {
+    fn from(_cce: CallContractError<T>) -> Self { Self::InvokeContractError }
+}
+
+/// Mapping errors related to CCD transfers to CustomContractError.
+impl From<TransferError> for CustomContractError {
+    fn from(_te: TransferError) -> Self { Self::InvokeTransferError }
+}
+
+/// Mapping errors related to contract upgrades to CustomContractError.
+impl From<UpgradeError> for CustomContractError {
+    #[inline(always)]
+    fn from(ue: UpgradeError) -> Self {
+        match ue {
+            UpgradeError::MissingModule => Self::FailedUpgradeMissingModule,
+            UpgradeError::MissingContract => Self::FailedUpgradeMissingContract,
+            UpgradeError::UnsupportedModuleVersion => Self::FailedUpgradeUnsupportedModuleVersion,
+        }
+    }
+}
+
+// Rating engines
+
+/// Number of fractional units in one whole rating point. All rating
+/// arithmetic is done in fixed point with this scale, so every node computes
+/// the exact same ratings.
+const RATING_SCALE: i128 = 1_000_000;
+
+/// ln(2) as a fixed-point number.
+const FP_LN2: i128 = 693_147;
+
+/// ln(10) as a fixed-point number.
+const FP_LN10: i128 = 2_302_585;
+
+/// pi^2 as a fixed-point number.
+const FP_PI_SQUARED: i128 = 9_869_604;
+
+/// The rating that the Glicko-2 scale is centered around.
+const GLICKO2_BASE_RATING: i128 = 1_500_000_000;
+
+/// Conversion factor between the Glicko and the Glicko-2 scale (173.7178).
+const GLICKO2_SCALE: i128 = 173_717_800;
+
+/// Tolerance of the Glicko-2 volatility iteration.
+const GLICKO2_CONVERGENCE: i128 = 1;
+
+/// Upper bound on the number of steps of the Glicko-2 volatility iteration.
+const GLICKO2_MAX_ITERATIONS: i128 = 100;
+
+/// Lower bound on the expected score used by Glicko-2. Keeps the estimated
+/// variance finite for very uneven battles.
+const GLICKO2_MIN_EXPECTED_SCORE: i128 = 100;
+
+/// The rating of newly added players: 1500 with a deviation of 350 and a
+/// volatility of 0.06.
+const DEFAULT_RATING: Rating = Rating {
+    rating: 1_500_000_000,
+    deviation: 350_000_000,
+    volatility: 60_000,
+};
+
+/// Default Elo k-factor.
+const DEFAULT_ELO_K_FACTOR: u64 = 32;
+
+/// Multiplies two fixed-point numbers.
+fn fp_mul(a: i128, b: i128) -> i128 { a * b / RATING_SCALE }
+
+/// Divides two fixed-point numbers.
+fn fp_div(a: i128, b: i128) -> i128 { a * RATING_SCALE / b }
+
+/// Computes e^x for a fixed-point number. Results below the fixed-point
+/// resolution are rounded to zero and the exponent is capped at 40.
+fn fp_exp(x: i128) -> i128 {
+    if x < -20 * RATING_SCALE {
+        return 0;
+    }
+    let x = x.min(40 * RATING_SCALE);
+    // Range reduction: e^x = 2^k * e^r with |r| <= ln(2) / 2.
+    let k = (x + x.signum() * FP_LN2 / 2) / FP_LN2;
+    let r = x - k * FP_LN2;
+    // Taylor series of e^r.
+    let mut term = RATING_SCALE;
+    let mut sum = RATING_SCALE;
+    let mut i = 1;
+    while term != 0 {
+        term = term * r / (i * RATING_SCALE);
+        sum += term;
+        i += 1;
+    }
+    if k >= 0 {
+        sum << (k as u32)
+    } else {
+        sum >> (-k as u32)
+    }
+}
+
+/// Computes the natural logarithm of a positive fixed-point number.
+/// Non-positive numbers are treated as the smallest positive number.
+fn fp_ln(x: i128) -> i128 {
+    // Normalize to y in [1, 2) with x = 2^k * y.
+    let mut y = x.max(1);
+    let mut k = 0;
+    while y >= 2 * RATING_SCALE {
+        y /= 2;
+        k += 1;
+    }
+    while y < RATING_SCALE {
+        y *= 2;
+        k -= 1;
+    }
+    // ln(y) = 2 * atanh(z) with z = (y - 1) / (y + 1) in [0, 1/3).
+    let z = fp_div(y - RATING_SCALE, y + RATING_SCALE);
+    let z_squared = fp_mul(z, z);
+    let mut term = z;
+    let mut sum = 0;
+    let mut n = 1;
+    while term != 0 {
+        sum += term / n;
+        term = fp_mul(term, z_squared);
+        n += 2;
+    }
+    k * FP_LN2 + 2 * sum
+}
+
+/// Computes the square root of a fixed-point number. Negative numbers are
+/// treated as zero.
+fn fp_sqrt(x: i128) -> i128 {
+    if x <= 0 {
+        return 0;
+    }
+    // Newton's method on the integer square root of x * RATING_SCALE.
+    let n = x * RATING_SCALE;
+    let mut root = n;
+    let mut next = (root + n / root) / 2;
+    while next < root {
+        root = next;
+        next = (root + n / root) / 2;
+    }
+    root
+}
+
+/// Converts a fixed-point number into a stored rating value, saturating at
+/// zero.
+fn to_rating_value(x: i128) -> u64 { x.max(0).min(i128::from(u64::MAX)) as u64 }
+
+/// A rating engine computes a player's new rating after a single battle.
+trait RatingEngine {
+    /// The rating of newly added players.
+    fn initial_rating(&self) -> Rating;
+
+    /// Returns the new rating of `player` after a battle against `opponent`
+    /// with the given result for `player`.
+    fn rate(&self, player: &Rating, opponent: &Rating, result: BattleResult) -> Rating;
+}
+
+impl RatingEngine for Elo {
+    fn initial_rating(&self) -> Rating { DEFAULT_RATING }
+
+    fn rate(&self, player: &Rating, opponent: &Rating, result: BattleResult) -> Rating {
+        // Expected score: 1 / (1 + 10^((opponent - player) / 400)).
+        let difference = i128::from(opponent.rating) - i128::from(player.rating);
+        let expected =
+            fp_div(RATING_SCALE, RATING_SCALE + fp_exp(fp_mul(difference, FP_LN10) / 400));
+        let change = i128::from(self.k_factor) * (result.score() - expected);
+        Rating {
+            rating: to_rating_value(i128::from(player.rating) + change),
+            ..*player
+        }
+    }
+}
+
+impl Glicko2 {
+    /// The Glicko-2 function g(phi).
+    fn g(phi: i128) -> i128 {
+        let phi_squared = fp_mul(phi, phi);
+        fp_div(RATING_SCALE, fp_sqrt(RATING_SCALE + fp_div(3 * phi_squared, FP_PI_SQUARED)))
+    }
+
+    /// Computes the new volatility using the Illinois algorithm (step 5 of
+    /// the Glicko-2 paper).
+    fn volatility(&self, delta: i128, phi: i128, variance: i128, sigma: i128) -> i128 {
+        let tau = i128::from(self.tau);
+        let tau_squared = fp_mul(tau, tau).max(1);
+        let phi_squared = fp_mul(phi, phi);
+        let delta_squared = fp_mul(delta, delta);
+        let a = fp_ln(fp_mul(sigma, sigma));
+        let f = |x: i128| {
+            let ex = fp_exp(x);
+            let denominator = phi_squared + variance + ex;
+            fp_div(
+                fp_mul(ex, delta_squared - phi_squared - variance - ex),
+                (2 * fp_mul(denominator, denominator)).max(1),
+            ) - fp_div(x - a, tau_squared)
+        };
+
+        let mut lower = a;
+        let mut upper = if delta_squared > phi_squared + variance {
+            fp_ln(delta_squared - phi_squared - variance)
+        } else {
+            let mut k = 1;
+            while k < GLICKO2_MAX_ITERATIONS && f(a - k * tau) < 0 {
+                k += 1;
+            }
+            a - k * tau
+        };
+        let mut f_lower = f(lower);
+        let mut f_upper = f(upper);
+        let mut iterations = 0;
+        while (upper - lower).abs() > GLICKO2_CONVERGENCE
+            && f_upper != f_lower
+            && iterations < GLICKO2_MAX_ITERATIONS
+        {
+            let c = lower + fp_div(fp_mul(lower - upper, f_lower), f_upper - f_lower);
+            let f_c = f(c);
+            if f_c.signum() * f_upper.signum() <= 0 {
+                lower = upper;
+                f_lower = f_upper;
+            } else {
+                f_lower /= 2;
+            }
+            upper = c;
+            f_upper = f_c;
+            iterations += 1;
+        }
+        fp_exp(lower / 2)
+    }
+}
+
+impl RatingEngine for Glicko2 {
+    fn initial_rating(&self) -> Rating { DEFAULT_RATING }
+
+    fn rate(&self, player: &Rating, opponent: &Rating, result: BattleResult) -> Rating {
+        // Convert to the Glicko-2 scale.
+        let mu = fp_div(i128::from(player.rating) - GLICKO2_BASE_RATING, GLICKO2_SCALE);
+        let phi = fp_div(i128::from(player.deviation), GLICKO2_SCALE);
+        let sigma = i128::from(player.volatility);
+        let opponent_mu = fp_div(i128::from(opponent.rating) - GLICKO2_BASE_RATING, GLICKO2_SCALE);
+        let opponent_phi = fp_div(i128::from(opponent.deviation), GLICKO2_SCALE);
+
+        // Estimated variance and improvement.
+        let g = Self::g(opponent_phi);
+        let expected = fp_div(RATING_SCALE, RATING_SCALE + fp_exp(-fp_mul(g, mu - opponent_mu)))
+            .max(GLICKO2_MIN_EXPECTED_SCORE)
+            .min(RATING_SCALE - GLICKO2_MIN_EXPECTED_SCORE);
+        let variance =
+            fp_div(RATING_SCALE, fp_mul(fp_mul(g, g), fp_mul(expected, RATING_SCALE - expected)));
+        let delta = fp_mul(fp_mul(variance, g), result.score() - expected);
+
+        // New volatility, deviation and rating.
+        let new_sigma = self.volatility(delta, phi, variance, sigma);
+        let phi_star_squared = (fp_mul(phi, phi) + fp_mul(new_sigma, new_sigma)).max(1);
+        let new_phi = fp_div(
+            RATING_SCALE,
+            fp_sqrt(fp_div(RATING_SCALE, phi_star_squared) + fp_div(RATING_SCALE, variance)),
+        );
+        let new_mu = mu + fp_mul(fp_mul(new_phi, new_phi), fp_mul(g, result.score() - expected));
+
+        // Convert back to the Glicko scale.
+        Rating {
+            rating: to_rating_value(fp_mul(new_mu, GLICKO2_SCALE) + GLICKO2_BASE_RATING),
+            deviation: to_rating_value(fp_mul(new_phi, GLICKO2_SCALE)),
+            volatility: to_rating_value(new_sigma),
+        }
+    }
+}
+
+impl RatingEngine for RatingEngineConfig {
+    fn initial_rating(&self) -> Rating {
+        match self {
+            RatingEngineConfig::Elo(elo) => elo.initial_rating(),
+            RatingEngineConfig::Glicko2(glicko2) => glicko2.initial_rating(),
+        }
+    }
+
+    fn rate(&self, player: &Rating, opponent: &Rating, result: BattleResult) -> Rating {
+        match self {
+            RatingEngineConfig::Elo(elo) => elo.rate(player, opponent, result),
+            RatingEngineConfig::Glicko2(glicko2) => glicko2.rate(player, opponent, result),
+        }
+    }
+}
+
+impl RatingEngineConfig {
+    /// Checks that the parameters of the engine are usable.
+    fn is_valid(&self) -> bool {
+        match self {
+            RatingEngineConfig::Elo(elo) => elo.k_factor > 0,
+            RatingEngineConfig::Glicko2(glicko2) => glicko2.tau > 0,
+        }
+    }
+}
+
+impl PlayerData {
+    /// Creates the data of a player without any results.
+    fn new(state: PlayerState, rating: Rating, registered_at: Timestamp) -> Self {
+        PlayerData {
+            state,
+            wins: 0,
+            losses: 0,
+            draws: 0,
+            score_for: 0,
+            score_against: 0,
+            rating,
+            registered_at,
+            last_played: None,
+            current_streak: 0,
+            best_win_streak: 0,
+            worst_loss_streak: 0,
+        }
+    }
+
+    /// The number of battles the player has played.
+    fn games_played(&self) -> u64 {
+        self.wins + self.losses + self.draws
+    }
+
+    /// The share of battles won in basis points, 0 if the player has not
+    /// played yet.
+    fn win_rate(&self) -> u16 {
+        match self.games_played() {
+            0 => 0,
+            games_played => {
+                (u128::from(self.wins) * u128::from(MAX_BASIS_POINTS) / u128::from(games_played))
+                    as u16
+            }
+        }
+    }
+
+    /// The stats of the player with the derived values.
+    fn stats(&self) -> PlayerStats {
+        PlayerStats {
+            version: PLAYER_STATS_VERSION,
+            state: self.state,
+            wins: self.wins,
+            losses: self.losses,
+            draws: self.draws,
+            games_played: self.games_played(),
+            win_rate: self.win_rate(),
+            score_for: self.score_for,
+            score_against: self.score_against,
+            rating: self.rating,
+            registered_at: self.registered_at,
+            last_played: self.last_played,
+            current_streak: self.current_streak,
+            best_win_streak: self.best_win_streak,
+            worst_loss_streak: self.worst_loss_streak,
+        }
+    }
+
+    /// The current streak as its kind and length, if the player is on a
+    /// streak.
+    fn streak(&self) -> Option<(StreakKind, u64)> {
+        match self.current_streak {
+            0 => None,
+            streak if streak > 0 => Some((StreakKind::Win, streak.unsigned_abs())),
+            streak => Some((StreakKind::Loss, streak.unsigned_abs())),
+        }
+    }
+
+    /// Records the result and the optional score of a battle against an
+    /// opponent with the given rating, played at `now`.
+    fn record_result(
+        &mut self,
+        result: BattleResult,
+        score: Option<MatchScore>,
+        opponent_rating: &Rating,
+        engine: &impl RatingEngine,
+        now: Timestamp,
+    ) {
+        match result {
+            BattleResult::Win => {
+                self.wins += 1;
+                self.current_streak = self.current_streak.max(0) + 1;
+                self.best_win_streak = self.best_win_streak.max(self.current_streak.unsigned_abs());
+            }
+            BattleResult::Loss => {
+                self.losses += 1;
+                self.current_streak = self.current_streak.min(0) - 1;
+                self.worst_loss_streak =
+                    self.worst_loss_streak.max(self.current_streak.unsigned_abs());
+            }
+            BattleResult::Draw => {
+                self.draws += 1;
+                self.current_streak = 0;
+            }
+        }
+        if let Some(score) = score {
+            self.score_for += score.score_for;
+            self.score_against += score.score_against;
+        }
+        self.rating = engine.rate(&self.rating, opponent_rating, result);
+        self.last_played = Some(now);
+    }
+
+    /// Records a battle between two players, rating each of them against the
+    /// rating of the other before the battle. The result and the score are
+    /// from the point of view of `first`.
+    fn record_battle(
+        first: &mut PlayerData,
+        second: &mut PlayerData,
+        result: BattleResult,
+        score: Option<MatchScore>,
+        engine: &impl RatingEngine,
+        now: Timestamp,
+    ) {
+        let first_rating = first.rating;
+        first.record_result(result, score, &second.rating, engine, now);
+        second.record_result(
+            result.reversed(),
+            score.map(MatchScore::reversed),
+            &first_rating,
+            engine,
+            now,
+        );
+    }
+
+    /// Rolls back a battle with the given result and optional score from the
+    /// point of view of the player. The counters and the rating are adjusted
+    /// by the change of the battle, which keeps later battles. The streaks
+    /// and the time of the last battle are restored only if the player has
+    /// not played since, because later battles decided them otherwise.
+    fn revert(&mut self, result: BattleResult, score: Option<MatchScore>, change: &PlayerChange) {
+        let played_since = self.games_played() != change.games_played;
+
+        match result {
+            BattleResult::Win => self.wins = self.wins.saturating_sub(1),
+            BattleResult::Loss => self.losses = self.losses.saturating_sub(1),
+            BattleResult::Draw => self.draws = self.draws.saturating_sub(1),
+        }
+        if let Some(score) = score {
+            self.score_for = self.score_for.saturating_sub(score.score_for);
+            self.score_against = self.score_against.saturating_sub(score.score_against);
+        }
+        self.rating = Rating {
+            rating: revert_rating_value(self.rating.rating, change.rating.rating),
+            deviation: revert_rating_value(self.rating.deviation, change.rating.deviation),
+            volatility: revert_rating_value(self.rating.volatility, change.rating.volatility),
+        };
+
+        if !played_since {
+            self.current_streak = change.current_streak;
+            self.best_win_streak = change.best_win_streak;
+            self.worst_loss_streak = change.worst_loss_streak;
+            self.last_played = change.last_played;
+        }
+    }
+}
+
+impl PlayerChange {
+    /// Keeps what a battle changed, given the data of the player before and
+    /// after the battle.
+    fn new(before: &PlayerData, after: &PlayerData) -> Self {
+        PlayerChange {
+            rating: RatingChange {
+                rating: rating_value_change(before.rating.rating, after.rating.rating),
+                deviation: rating_value_change(before.rating.deviation, after.rating.deviation),
+                volatility: rating_value_change(
+                    before.rating.volatility,
+                    after.rating.volatility,
+                ),
+            },
+            games_played: after.games_played(),
+            current_streak: before.current_streak,
+            best_win_streak: before.best_win_streak,
+            worst_loss_streak: before.worst_loss_streak,
+            last_played: before.last_played,
+        }
+    }
+}
+
+/// The change from `before` to `after` of a rating value.
+fn rating_value_change(before: u64, after: u64) -> i64 {
+    let change = i128::from(after) - i128::from(before);
+    change.clamp(i128::from(i64::MIN), i128::from(i64::MAX)) as i64
+}
+
+/// Removes a change from a rating value.
+fn revert_rating_value(current: u64, change: i64) -> u64 {
+    to_rating_value(i128::from(current) - i128::from(change))
+}
+
+impl<S: HasStateApi> State<S> {
+    /// Creates the new state of the `state` contract with no one having any
+    /// data by default. The ProtocolAddressesState is uninitialized.
+    /// The ProtocolAddressesState has to be set with the `initialize`
+    /// function after the `proxy` contract is deployed.
+    fn new(state_builder: &mut StateBuilder<S>, admin: Address) -> Self {
+        // Grant the operational roles to the admin, so that a fresh instance
+        // can be used without granting roles first.
+        let mut roles = state_builder.new_set();
+        for role in OPERATIONAL_ROLES {
+            roles.insert((admin, role));
+        }
+
+        // Setup state.
+        State {
+            version: StateVersion(STATE_VERSION),
+            admin,
+            pending_admin: None,
+            pending_upgrade: None,
+            multisig: None,
+            proposals: state_builder.new_map(),
+            next_proposal_id: 0,
+            roles,
+            game_servers: state_builder.new_map(),
+            player_data: state_builder.new_map(),
+            player_count: 0,
+            suspensions: state_builder.new_map(),
+            battles: state_builder.new_map(),
+            pending_results: state_builder.new_map(),
+            dispute_window: DEFAULT_DISPUTE_WINDOW,
+            battle_changes: state_builder.new_map(),
+            disputes: state_builder.new_map(),
+            current_season: 0,
+            seasons: state_builder.new_map(),
+            season_player_data: state_builder.new_map(),
+            paused: false,
+            rating_engine: RatingEngineConfig::Elo(Elo {
+                k_factor: DEFAULT_ELO_K_FACTOR,
+            }),
+            registration: RegistrationConfig {
+                fee: RegistrationFee::Closed,
+                requires_approval: false,
+            },
+            treasury: Amount::zero(),
+            challenges: state_builder.new_map(),
+            next_challenge_id: 0,
+            wager_fee: 0,
+            prize_tokens: state_builder.new_set(),
+            prize_pools: state_builder.new_map(),
+            leaderboard_config: LeaderboardConfig {
+                ranking: LeaderboardRanking::Rating,
+                size: MAX_LEADERBOARD_SIZE,
+            },
+            leaderboard: state_builder.new_box(Vec::new()),
+            streak_milestones: vec![5, 10, 25],
+            validation_mode: ValidationMode::Lenient,
+            implementors: state_builder.new_map(),
+        }
+    }
+
+    /// Checks whether an address holds a role. The admin address always holds
+    /// the `Admin` role.
+    fn has_role(&self, address: &Address, role: Role) -> bool {
+        (role == Role::Admin && *address == self.admin) || self.roles.contains(&(*address, role))
+    }
+
+    /// Checks whether contracts implementing a standard on behalf of this
+    /// contract have been set.
+    fn have_implementors(&self, std_id: &StandardIdentifierOwned) -> SupportResult {
+        if let Some(addresses) = self.implementors.get(std_id) {
+            SupportResult::SupportBy(addresses.to_vec())
+        } else {
+            SupportResult::NoSupport
+        }
+    }
+
+    /// Sets the contracts implementing a standard on behalf of this contract.
+    /// An empty list removes the implementors.
+    fn set_implementors(
+        &mut self,
+        std_id: StandardIdentifierOwned,
+        implementors: Vec<ContractAddress>,
+    ) {
+        if implementors.is_empty() {
+            self.implementors.remove(&std_id);
+        } else {
+            self.implementors.insert(std_id, implementors);
+        }
+    }
+
+    /// Enables or disables a game server, keeping the nonce of a known server.
+    fn set_game_server(&mut self, public_key: PublicKeyEd25519, enabled: bool) {
+        let nonce = self.game_servers.get(&public_key).map_or(0, |server| server.nonce);
+        self.game_servers.insert(public_key, GameServer {
+            enabled,
+            nonce,
+        });
+    }
+
+    /// Checks that the sender is a signer of the enabled multisig and returns
+    /// its account.
+    fn ensure_signer(&self, sender: &Address) -> ContractResult<AccountAddress> {
+        let multisig = self.multisig.as_ref().ok_or(ContractError::MultisigNotEnabled)?;
+        match sender {
+            Address::Account(account) if multisig.signers.contains(account) => Ok(*account),
+            _ => bail!(ContractError::Unauthorized),
+        }
+    }
+
+    /// Stores a new proposal and returns its id.
+    fn submit_proposal(
+        &mut self,
+        proposer: AccountAddress,
+        action: ProposalAction,
+        expiry: Timestamp,
+    ) -> ProposalId {
+        let proposal_id = self.next_proposal_id;
+        self.next_proposal_id += 1;
+        self.proposals.insert(proposal_id, Proposal {
+            action,
+            proposer,
+            approvals: Vec::new(),
+            expiry,
+            executed: false,
+        });
+        proposal_id
+    }
+
+    /// Records the approval of a signer. Only approvals of current signers
+    /// count towards the threshold. Returns the number of approvals and, if
+    /// the threshold is reached, the action to run, which marks the proposal
+    /// as executed.
+    fn approve_proposal(
+        &mut self,
+        proposal_id: ProposalId,
+        signer: AccountAddress,
+        now: Timestamp,
+    ) -> ContractResult<(u32, Option<ProposalAction>)> {
+        let multisig = self.multisig.as_ref().ok_or(ContractError::MultisigNotEnabled)?;
+        let mut proposal =
+            self.proposals.get_mut(&proposal_id).ok_or(ContractError::ProposalDoesNotExist)?;
+        ensure!(!proposal.executed, ContractError::ProposalAlreadyExecuted);
+        ensure!(now < proposal.expiry, ContractError::ProposalExpired);
+        ensure!(!proposal.approvals.contains(&signer), ContractError::AlreadyApproved);
+        proposal.approvals.push(signer);
+
+        let approvals = proposal
+            .approvals
+            .iter()
+            .filter(|approval| multisig.signers.contains(approval))
+            .count() as u32;
+        if approvals < multisig.threshold {
+            return Ok((approvals, None));
+        }
+        proposal.executed = true;
+        Ok((approvals, Some(proposal.action.clone())))
+    }
+
+    /// Schedules an upgrade, replacing any scheduled upgrade.
+    fn schedule_upgrade(&mut self, params: UpgradeParams, now: Timestamp) -> ContractResult<()> {
+        let earliest = now.checked_add(UPGRADE_DELAY).ok_or(ContractError::UpgradeTooEarly)?;
+        ensure!(params.execute_after >= earliest, ContractError::UpgradeTooEarly);
+        self.pending_upgrade = Some(params);
+        Ok(())
+    }
+
+    /// Records both sides of a reported battle in the season in progress, and
+    /// keeps what the battle changed so that it can be rolled back. Nothing
+    /// is kept for a battle whose result has already been disputed, since a
+    /// battle can only be disputed once. The state of the players is not
+    /// checked, see `report_match`. Returns the new lifetime data of both
+    /// players.
+    fn record_reported_match(
+        &mut self,
+        battle_id: BattleId,
+        winner: Address,
+        loser: Address,
+        result: BattleResult,
+        score: Option<MatchScore>,
+        now: Timestamp,
+    ) -> ContractResult<(PlayerData, PlayerData)> {
+        let winner_before = self.get_player(&winner).ok_or(ContractError::PlayerDoesNotExist)?;
+        let loser_before = self.get_player(&loser).ok_or(ContractError::PlayerDoesNotExist)?;
+        let season_before = self.active_season().map(|season| {
+            (
+                season,
+                self.season_player_or_new(season, &winner, winner_before.state, now),
+                self.season_player_or_new(season, &loser, loser_before.state, now),
+            )
+        });
+        let season = season_before.as_ref().map(|(season, ..)| *season);
+        let (winner_data, loser_data) =
+            self.record_match_in_season(winner, loser, result, score, now, season)?;
+        if self.disputes.get(&battle_id).is_none() {
+            let season = season_before.map(|(season, winner_before, loser_before)| SeasonChange {
+                season,
+                winner: PlayerChange::new(
+                    &winner_before,
+                    &self.season_player_or_new(season, &winner, winner_data.state, now),
+                ),
+                loser: PlayerChange::new(
+                    &loser_before,
+                    &self.season_player_or_new(season, &loser, loser_data.state, now),
+                ),
+            });
+            self.battle_changes.insert(battle_id, BattleChange {
+                winner: PlayerChange::new(&winner_before, &winner_data),
+                loser: PlayerChange::new(&loser_before, &loser_data),
+                season,
+            });
+        }
+        Ok((winner_data, loser_data))
+    }
+
+    /// Rolls back what a reported battle changed in the lifetime data of both
+    /// players, and in the season standings if the season of the battle is
+    /// still in progress. An ended season is not changed, since it may have
+    /// paid out its prizes. Returns the season the battle was rolled back in.
+    fn revert_battle(&mut self, battle_id: BattleId) -> ContractResult<Option<SeasonId>> {
+        let battle = self.battles.get(&battle_id).map(|battle| (*battle).clone());
+        let battle = battle.ok_or(ContractError::BattleDoesNotExist)?;
+        let change = self.battle_changes.get(&battle_id).map(|change| (*change).clone());
+        let change = change.ok_or(ContractError::BattleDoesNotExist)?;
+        self.battle_changes.remove(&battle_id);
+
+        let winner_result = if battle.draw {
+            BattleResult::Draw
+        } else {
+            BattleResult::Win
+        };
+        let loser_result = winner_result.reversed();
+        let loser_score = battle.score.map(MatchScore::reversed);
+
+        let sides = [
+            (battle.winner, winner_result, battle.score, change.winner),
+            (battle.loser, loser_result, loser_score, change.loser),
+        ];
+        for (player, result, score, change) in sides {
+            let mut player_data =
+                self.get_player(&player).ok_or(ContractError::PlayerDoesNotExist)?;
+            player_data.revert(result, score, &change);
+            self.player_data.insert(player, player_data.clone());
+            self.rank_player(player, &player_data);
+        }
+
+        let season_change = match change.season {
+            Some(season_change) if self.active_season() == Some(season_change.season) => {
+                season_change
+            }
+            _ => return Ok(None),
+        };
+        let season = season_change.season;
+        let sides = [
+            (battle.winner, winner_result, battle.score, season_change.winner),
+            (battle.loser, loser_result, loser_score, season_change.loser),
+        ];
+        for (player, result, score, change) in sides {
+            if let Some(mut season_data) = self.get_season_player(season, &player) {
+                season_data.revert(result, score, &change);
+                self.set_season_player(season, player, season_data);
+            }
+        }
+        Ok(Some(season))
+    }
+
+    /// Rolls back a reported battle and records the reversed result, in which
+    /// the loser won. A draw cannot be reversed. The reversed result counts
+    /// for the season of the battle only if the battle was rolled back in it.
+    /// Since the battle has been disputed, nothing is kept to roll the
+    /// reversed result back.
+    fn overturn_battle(&mut self, battle_id: BattleId, now: Timestamp) -> ContractResult<()> {
+        let battle = self.battles.get(&battle_id).map(|battle| (*battle).clone());
+        let battle = battle.ok_or(ContractError::BattleDoesNotExist)?;
+        ensure!(!battle.draw, ContractError::InvalidDecision);
+
+        let season = self.revert_battle(battle_id)?;
+        let score = battle.score.map(MatchScore::reversed);
+        self.record_match_in_season(
+            battle.loser,
+            battle.winner,
+            BattleResult::Win,
+            score,
+            now,
+            season,
+        )?;
+        self.battles.insert(battle_id, BattleRecord {
+            winner: battle.loser,
+            loser: battle.winner,
+            score,
+            ..battle
+        });
+        Ok(())
+    }
+
+    /// Returns a copy of a proposed result that has not been finalized.
+    fn get_pending_result(&self, battle_id: BattleId) -> ContractResult<PendingResult> {
+        self.pending_results
+            .get(&battle_id)
+            .map(|pending_result| (*pending_result).clone())
+            .ok_or(ContractError::PendingResultDoesNotExist)
+    }
+
+    /// Returns a copy of the data of a player, if the player has been added.
+    fn get_player(&self, player: &Address) -> Option<PlayerData> {
+        self.player_data.get(player).map(|data| (*data).clone())
+    }
+
+    /// Checks that a player has been added and can record battles, that is,
+    /// is neither suspended nor waiting for approval.
+    fn ensure_can_battle(&self, player: &Address) -> ContractResult<()> {
+        let player_data = self.player_data.get(player).ok_or(ContractError::PlayerDoesNotExist)?;
+        match player_data.state {
+            PlayerState::Active => Ok(()),
+            PlayerState::Suspended => bail!(ContractError::PlayerSuspended),
+            PlayerState::PendingApproval => bail!(ContractError::PlayerNotActive),
+        }
+    }
+
+    /// Checks that a player has been added and is active.
+    fn ensure_active(&self, player: &Address) -> ContractResult<()> {
+        let player_data = self.player_data.get(player).ok_or(ContractError::PlayerDoesNotExist)?;
+        ensure_eq!(player_data.state, PlayerState::Active, ContractError::PlayerNotActive);
+        Ok(())
+    }
+
+    /// Settles an accepted challenge with the result of the battle between
+    /// `winner` and `loser`. The winner gets the pot minus the wager fee,
+    /// which is added to the treasury, and a draw refunds both stakes.
+    /// Returns the CCD transfers to make and the event to log.
+    fn settle_challenge(
+        &mut self,
+        challenge_id: ChallengeId,
+        battle_id: BattleId,
+        winner: &Address,
+        loser: &Address,
+        draw: bool,
+    ) -> ContractResult<(Vec<(AccountAddress, Amount)>, ChallengeSettledEvent)> {
+        let wager_fee = self.wager_fee;
+        let mut challenge =
+            self.challenges.get_mut(&challenge_id).ok_or(ContractError::ChallengeDoesNotExist)?;
+        ensure_eq!(
+            challenge.status,
+            ChallengeStatus::Accepted,
+            ContractError::InvalidChallengeStatus
+        );
+
+        let challenger = Address::Account(challenge.challenger);
+        let opponent = Address::Account(challenge.opponent);
+        let winner_account = if *winner == challenger && *loser == opponent {
+            challenge.challenger
+        } else if *winner == opponent && *loser == challenger {
+            challenge.opponent
+        } else {
+            bail!(ContractError::ChallengeMismatch);
+        };
+        challenge.status = ChallengeStatus::Settled;
+
+        let stake = challenge.stake;
+        if draw {
+            let refunds = vec![(challenge.challenger, stake), (challenge.opponent, stake)];
+            return Ok((refunds, ChallengeSettledEvent {
+                challenge: challenge_id,
+                battle_id,
+                winner: None,
+                payout: Amount::zero(),
+                fee: Amount::zero(),
+            }));
+        }
+
+        let pot = stake.micro_ccd * 2;
+        let fee = Amount::from_micro_ccd(
+            (u128::from(pot) * u128::from(wager_fee) / u128::from(MAX_BASIS_POINTS)) as u64,
+        );
+        let payout = Amount::from_micro_ccd(pot) - fee;
+        drop(challenge);
+        self.treasury += fee;
+
+        Ok((vec![(winner_account, payout)], ChallengeSettledEvent {
+            challenge: challenge_id,
+            battle_id,
+            winner: Some(winner_account),
+            payout,
+            fee,
+        }))
+    }
+
+    /// Ends the suspension of a player if its time has passed and restores
+    /// the state the player had before. Returns the restored state if the
+    /// player was reinstated.
+    fn lift_expired_suspension(&mut self, player: &Address, now: Timestamp) -> Option<PlayerState> {
+        let previous_state = match self.suspensions.get(player) {
+            Some(suspension) if suspension.until <= now => suspension.previous_state,
+            _ => return None,
+        };
+        self.suspensions.remove(player);
+        if let Some(mut player_data) = self.player_data.get_mut(player) {
+            player_data.state = previous_state;
+        }
+        Some(previous_state)
+    }
+
+    /// Returns the state of a player as of `now`, treating a suspension whose
+    /// time has passed as lifted even before the player is reinstated.
+    fn effective_state(&self, player: &Address, state: PlayerState, now: Timestamp) -> PlayerState {
+        match self.suspensions.get(player) {
+            Some(suspension) if suspension.until <= now => suspension.previous_state,
+            _ => state,
+        }
+    }
+
+    /// Returns the season in progress, if any.
+    fn active_season(&self) -> Option<SeasonId> {
+        let season = self.seasons.get(&self.current_season)?;
+        if season.ended_at.is_none() {
+            Some(self.current_season)
+        } else {
+            None
+        }
+    }
+
+    /// Returns a copy of the data of a player in a season, if the player has
+    /// a result in the season.
+    fn get_season_player(&self, season: SeasonId, player: &Address) -> Option<PlayerData> {
+        self.season_player_data.get(&(season, *player)).map(|data| (*data).clone())
+    }
+
+    /// Returns a copy of the data of a player in a season, or the data a
+    /// player without results in the season starts from.
+    fn season_player_or_new(
+        &self,
+        season: SeasonId,
+        player: &Address,
+        state: PlayerState,
+        now: Timestamp,
+    ) -> PlayerData {
+        self.get_season_player(season, player)
+            .unwrap_or_else(|| PlayerData::new(state, self.rating_engine.initial_rating(), now))
+    }
+
+    /// Stores the data of a player in a season and counts the players of the
+    /// season.
+    fn set_season_player(&mut self, season: SeasonId, player: Address, data: PlayerData) {
+        if self.season_player_data.insert((season, player), data).is_none() {
+            if let Some(mut info) = self.seasons.get_mut(&season) {
+                info.players += 1;
+            }
+        }
+    }
+
+    /// Starts the next season and returns its id.
+    fn start_season(&mut self, now: Timestamp) -> SeasonId {
+        self.current_season += 1;
+        self.seasons.insert(self.current_season, SeasonInfo {
+            started_at: now,
+            ended_at: None,
+            players: 0,
+        });
+        self.current_season
+    }
+
+    /// Moves a player to its rank on the leaderboard after its data changed.
+    /// The player drops off the leaderboard if it ranks below a full
+    /// leaderboard. Players off the leaderboard are only ranked again with
+    /// their next result or by `rebuild_leaderboard`.
+    fn rank_player(&mut self, player: Address, player_data: &PlayerData) {
+        let key = self.leaderboard_config.ranking.key(player_data);
+        let size = self.leaderboard_config.size as usize;
+
+        let mut leaderboard = self.leaderboard.get_mut();
+        leaderboard.retain(|entry| entry.player != player);
+        // Players with the same key keep the rank they reached first.
+        let rank =
+            leaderboard.iter().position(|entry| entry.key < key).unwrap_or(leaderboard.len());
+        if rank < size {
+            leaderboard.insert(rank, LeaderboardEntry {
+                player,
+                key,
+            });
+            leaderboard.truncate(size);
+        }
+    }
+
+    /// Ranks every player from scratch, e.g. after the leaderboard settings
+    /// changed.
+    fn rebuild_leaderboard(&mut self) {
+        let ranking = self.leaderboard_config.ranking;
+        let mut leaderboard: Vec<LeaderboardEntry> = self
+            .player_data
+            .iter()
+            .map(|(player, player_data)| LeaderboardEntry {
+                player: *player,
+                key: ranking.key(&player_data),
+            })
+            .collect();
+        leaderboard.sort_by(|a, b| b.key.cmp(&a.key));
+        leaderboard.truncate(self.leaderboard_config.size as usize);
+        *self.leaderboard.get_mut() = leaderboard;
+    }
+
+    /// Records one side of a battle for a player, both in the lifetime totals
+    /// and in the season in progress. The player is rated against the
+    /// opponent, or against the initial rating if the opponent is not given
+    /// or not added. Players who are suspended or wait for approval cannot
+    /// record battles. Returns the new lifetime data of the player.
+    fn record_result(
+        &mut self,
+        player: Address,
+        result: BattleResult,
+        score: Option<MatchScore>,
+        opponent: Option<Address>,
+        now: Timestamp,
+    ) -> ContractResult<PlayerData> {
+        let engine = self.rating_engine;
+        let initial_rating = engine.initial_rating();
+
+        self.ensure_can_battle(&player)?;
+        let mut player_data = self.get_player(&player).ok_or(ContractError::PlayerDoesNotExist)?;
+        let opponent_rating = opponent
+            .and_then(|opponent| self.get_player(&opponent))
+            .map_or(initial_rating, |data| data.rating);
+        player_data.record_result(result, score, &opponent_rating, &engine, now);
+        self.player_data.insert(player, player_data.clone());
+        self.rank_player(player, &player_data);
+
+        if let Some(season) = self.active_season() {
+            let mut season_data = self
+                .get_season_player(season, &player)
+                .unwrap_or_else(|| PlayerData::new(player_data.state, initial_rating, now));
+            let opponent_rating = opponent
+                .and_then(|opponent| self.get_season_player(season, &opponent))
+                .map_or(initial_rating, |data| data.rating);
+            season_data.record_result(result, score, &opponent_rating, &engine, now);
+            self.set_season_player(season, player, season_data);
+        }
+
+        Ok(player_data)
+    }
+
+    /// Records both sides of a battle, both in the lifetime totals and in the
+    /// given season, if any. The result and the score are from the point of
+    /// view of `first`. The state of the players is not checked, so that an
+    /// arbitrator can correct a battle of a player who has since been
+    /// suspended. Returns the new lifetime data of both players.
+    fn record_match_in_season(
+        &mut self,
+        first: Address,
+        second: Address,
+        result: BattleResult,
+        score: Option<MatchScore>,
+        now: Timestamp,
+        season: Option<SeasonId>,
+    ) -> ContractResult<(PlayerData, PlayerData)> {
+        let engine = self.rating_engine;
+
+        let mut first_data = self.get_player(&first).ok_or(ContractError::PlayerDoesNotExist)?;
+        let mut second_data = self.get_player(&second).ok_or(ContractError::PlayerDoesNotExist)?;
+        PlayerData::record_battle(&mut first_data, &mut second_data, result, score, &engine, now);
+        self.player_data.insert(first, first_data.clone());
+        self.player_data.insert(second, second_data.clone());
+        self.rank_player(first, &first_data);
+        self.rank_player(second, &second_data);
+
+        if let Some(season) = season {
+            let mut first_season_data =
+                self.season_player_or_new(season, &first, first_data.state, now);
+            let mut second_season_data =
+                self.season_player_or_new(season, &second, second_data.state, now);
+            PlayerData::record_battle(
+                &mut first_season_data,
+                &mut second_season_data,
+                result,
+                score,
+                &engine,
+                now,
+            );
+            self.set_season_player(season, first, first_season_data);
+            self.set_season_player(season, second, second_season_data);
+        }
+
+        Ok((first_data, second_data))
+    }
+}
+
+/// Adds a player or changes the state of an existing player, and logs the
+/// matching event. New players are registered at `now`. In `Strict` mode
+/// only valid state transitions are accepted.
+fn set_player_data<S: HasStateApi>(
+    state: &mut State<S>,
+    params: &UpdatePlayerStateParams,
+    now: Timestamp,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    let initial_rating = state.rating_engine.initial_rating();
+    let current_state = state.player_data.get(&params.player).map(|data| data.state);
+    let is_new = current_state.is_none();
+    if state.validation_mode == ValidationMode::Strict {
+        ensure!(
+            PlayerState::is_valid_transition(current_state, params.state),
+            ContractError::InvalidStateTransition
+        );
+    }
+    // Setting the state directly replaces any timed suspension.
+    state.suspensions.remove(&params.player);
+
+    state
+        .player_data
+        .entry(params.player)
+        .and_modify(|pd| pd.state = params.state)
+        .or_insert(PlayerData::new(params.state, initial_rating, now));
+
+    if is_new {
+        state.player_count += 1;
+        logger.log(&VersusEvent::PlayerRegistered(PlayerRegisteredEvent {
+            player: params.player,
+            state: params.state,
+        }))?;
+    } else {
+        logger.log(&VersusEvent::PlayerStateChanged(PlayerStateChangedEvent {
+            player: params.player,
+            state: params.state,
+        }))?;
+    }
+
+    Ok(())
+}
+
+/// Records one side of a battle played at `now` and logs the result. Nothing
+/// is changed if the result cannot be recorded.
+fn apply_battle_result<S: HasStateApi>(
+    state: &mut State<S>,
+    params: &UpdateBattleResultParams,
+    now: Timestamp,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    if let Some(score) = params.score {
+        ensure!(score.agrees_with(params.result), ContractError::InvalidScore);
+    }
+
+    reinstate_if_expired(state, &params.player, now, logger)?;
+    let player_data =
+        state.record_result(params.player, params.result, params.score, params.opponent, now)?;
+
+    logger.log(&VersusEvent::BattleResult(BattleResultEvent {
+        player: params.player,
+        result: params.result,
+        score: params.score,
+        rating: player_data.rating,
+    }))?;
+
+    log_streak_milestone(&state.streak_milestones, params.player, &player_data, logger)?;
+
+    Ok(())
+}
+
+/// Reinstates a player whose suspension has ended and logs the state change.
+fn reinstate_if_expired<S: HasStateApi>(
+    state: &mut State<S>,
+    player: &Address,
+    now: Timestamp,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    if let Some(restored_state) = state.lift_expired_suspension(player, now) {
+        logger.log(&VersusEvent::PlayerStateChanged(PlayerStateChangedEvent {
+            player: *player,
+            state: restored_state,
+        }))?;
+    }
+    Ok(())
+}
+
+/// Logs a `StreakMilestone` event if the player's current streak just reached
+/// one of the milestones.
+fn log_streak_milestone(
+    milestones: &[u64],
+    player: Address,
+    player_data: &PlayerData,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    if let Some((kind, streak)) = player_data.streak() {
+        if milestones.contains(&streak) {
+            logger.log(&VersusEvent::StreakMilestone(StreakMilestoneEvent {
+                player,
+                kind,
+                streak,
+            }))?;
+        }
+    }
+    Ok(())
+}
+
+/// Reports both results of a battle, settles the challenge on it, if any, and
+/// pays out the pot. Suspended players and players waiting for approval
+/// cannot report battles, unless an arbitrator decided the result, as when
+/// overturning a reported battle.
+fn report_match<S: HasStateApi>(
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    params: ReportMatchParams,
+    now: Timestamp,
+    arbitrated: bool,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    let winner_result = params.winner_result()?;
+
+    let state = host.state_mut();
+    // Check that the battle has not been reported before.
+    ensure!(state.battles.get(&params.battle_id).is_none(), ContractError::BattleAlreadyReported);
+    // Check that no result of the battle is awaiting confirmation. Finalizing
+    // a proposed result removes it before reporting it.
+    ensure!(
+        state.pending_results.get(&params.battle_id).is_none(),
+        ContractError::ResultAlreadyProposed
+    );
+    reinstate_if_expired(state, &params.winner, now, logger)?;
+    reinstate_if_expired(state, &params.loser, now, logger)?;
+    if !arbitrated {
+        state.ensure_can_battle(&params.winner)?;
+        state.ensure_can_battle(&params.loser)?;
+    }
+
+    // Settle the wager on the battle, if any.
+    let settlement = match params.challenge {
+        Some(challenge) => Some(state.settle_challenge(
+            challenge,
+            params.battle_id,
+            &params.winner,
+            &params.loser,
+            params.draw,
+        )?),
+        None => None,
+    };
+
+    let (winner_data, loser_data) = state.record_reported_match(
+        params.battle_id,
+        params.winner,
+        params.loser,
+        winner_result,
+        params.score,
+        now,
+    )?;
+
+    state.battles.insert(params.battle_id, BattleRecord {
+        winner: params.winner,
+        loser: params.loser,
+        draw: params.draw,
+        score: params.score,
+        challenge: params.challenge,
+        reported_at: now,
+        voided: false,
+    });
+
+    logger.log(&VersusEvent::MatchReported(MatchReportedEvent {
+        battle_id: params.battle_id,
+        winner: params.winner,
+        loser: params.loser,
+        draw: params.draw,
+        score: params.score,
+        winner_rating: winner_data.rating,
+        loser_rating: loser_data.rating,
+    }))?;
+
+    let milestones = &host.state().streak_milestones;
+    log_streak_milestone(milestones, params.winner, &winner_data, logger)?;
+    log_streak_milestone(milestones, params.loser, &loser_data, logger)?;
+
+    // Pay out the pot of the settled challenge.
+    if let Some((transfers, event)) = settlement {
+        for (account, amount) in transfers {
+            host.invoke_transfer(&account, amount)?;
+        }
+        logger.log(&VersusEvent::ChallengeSettled(event))?;
+    }
+
+    Ok(())
+}
+
+/// Finalizes a proposed result, after it has been removed from the pending
+/// results, by reporting it. `arbitrated` is set when an arbitrator decided
+/// the result, see `report_match`.
+fn finalize_result<S: HasStateApi>(
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    pending_result: PendingResult,
+    now: Timestamp,
+    arbitrated: bool,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    logger.log(&VersusEvent::ResultFinalized(ResultFinalizedEvent {
+        battle_id: pending_result.report.battle_id,
+    }))?;
+    report_match(host, pending_result.report, now, arbitrated, logger)
+}
+
+/// Applies every item of a batch and returns the outcome of each item. In
+/// `AllOrNothing` mode the first item that cannot be applied rejects the
+/// whole batch. In `SkipInvalid` mode such items are skipped, except when
+/// logging fails, which always rejects.
+fn apply_batch<T>(
+    mode: BatchMode,
+    items: Vec<T>,
+    mut apply: impl FnMut(T) -> ContractResult<()>,
+) -> ContractResult<Vec<BatchItemOutcome>> {
+    let mut outcomes = Vec::with_capacity(items.len());
+    for item in items {
+        match apply(item) {
+            Ok(()) => outcomes.push(BatchItemOutcome::Applied),
+            Err(error)
+                if mode == BatchMode::SkipInvalid
+                    && !matches!(
+                        error,
+                        ContractError::LogFull | ContractError::LogMalformed
+                    ) =>
+            {
+                outcomes.push(BatchItemOutcome::Skipped(error))
+            }
+            Err(error) => return Err(error),
+        }
+    }
+    Ok(outcomes)
+}
+
+/// Pays out a prize by transferring the tokens from the prize pool to the
+/// receiver through the `transfer` entrypoint of the token contract. The
+/// balance is reduced before the token contract is invoked.
+fn pay_prize<S: HasStateApi>(
+    self_address: ContractAddress,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    payout: PrizePayout,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    {
+        let mut balance = host
+            .state_mut()
+            .prize_pools
+            .get_mut(&payout.key)
+            .ok_or(ContractError::InsufficientFunds)?;
+        ensure!(*balance >= payout.amount, ContractError::InsufficientFunds);
+        *balance -= payout.amount;
+    }
+
+    logger.log(&VersusEvent::PrizePaid(PrizePaidEvent {
+        key: payout.key.clone(),
+        amount: payout.amount,
+        to: payout.to.address(),
+    }))?;
+
+    let transfer = Transfer {
+        token_id: payout.key.token_id,
+        amount: payout.amount,
+        from: Address::Contract(self_address),
+        to: payout.to,
+        data: AdditionalData::empty(),
+    };
+    host.invoke_contract(
+        &payout.key.token_contract,
+        &TransferParams::from(vec![transfer]),
+        EntrypointName::new_unchecked("transfer"),
+        Amount::zero(),
+    )?;
+
+    Ok(())
+}
+
+/// Upgrades the contract to a new module and calls the migration function,
+/// if any.
+fn upgrade<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<S>,
+    params: UpgradeParams,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    // Trigger the upgrade.
+    host.upgrade(params.module)?;
+    logger.log(&VersusEvent::Upgrade(UpgradeEvent {
+        module: params.module,
+    }))?;
+    // Call the migration function if provided.
+    if let Some((func, parameters)) = params.migrate {
+        host.invoke_contract_raw(
+            &ctx.self_address(),
+            parameters.as_parameter(),
+            func.as_entrypoint_name(),
+            Amount::zero(),
+        )?;
+    }
+    Ok(())
+}
+
+/// Records the approval of a signer and runs the proposal once it has enough
+/// approvals.
+fn approve_and_execute<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    proposal_id: ProposalId,
+    signer: AccountAddress,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    let now = ctx.metadata().slot_time();
+    let (approvals, action) = host.state_mut().approve_proposal(proposal_id, signer, now)?;
+    logger.log(&VersusEvent::ProposalApproved(ProposalApprovedEvent {
+        proposal: proposal_id,
+        signer,
+        approvals,
+    }))?;
+
+    let action = match action {
+        Some(action) => action,
+        None => return Ok(()),
+    };
+    logger.log(&VersusEvent::ProposalExecuted(ProposalExecutedEvent {
+        proposal: proposal_id,
+    }))?;
+
+    let state = host.state_mut();
+    match action {
+        ProposalAction::ScheduleUpgrade(params) => {
+            let module = params.module;
+            let execute_after = params.execute_after;
+            state.schedule_upgrade(params, now)?;
+            logger.log(&VersusEvent::UpgradeScheduled(UpgradeScheduledEvent {
+                module,
+                execute_after,
+            }))?;
+        }
+        ProposalAction::CancelUpgrade => {
+            let pending_upgrade =
+                state.pending_upgrade.take().ok_or(ContractError::NoPendingUpgrade)?;
+            logger.log(&VersusEvent::UpgradeCancelled(UpgradeCancelledEvent {
+                module: pending_upgrade.module,
+            }))?;
+        }
+        ProposalAction::SetPaused(paused) => {
+            state.paused = paused;
+            logger.log(&VersusEvent::Paused(PausedEvent {
+                paused,
+            }))?;
+        }
+        ProposalAction::ProposeAdmin(params) => {
+            if let Some(expiry) = params.expiry {
+                ensure!(expiry > now, ContractError::InvalidExpiry);
+            }
+            state.pending_admin = Some(PendingAdmin {
+                address: params.new_admin,
+                expiry: params.expiry,
+            });
+            logger.log(&VersusEvent::AdminProposed(AdminProposedEvent {
+                new_admin: params.new_admin,
+                expiry: params.expiry,
+            }))?;
+        }
+        ProposalAction::WithdrawTreasury(params) => {
+            ensure!(params.amount <= state.treasury, ContractError::InsufficientFunds);
+            // Update the treasury before transferring.
+            state.treasury -= params.amount;
+            host.invoke_transfer(&params.to, params.amount)?;
+            logger.log(&VersusEvent::TreasuryWithdrawal(TreasuryWithdrawalEvent {
+                to: params.to,
+                amount: params.amount,
+            }))?;
+        }
+        ProposalAction::SetMultisig(config) => {
+            if let Some(config) = &config {
+                ensure!(config.is_valid(), ContractError::InvalidMultisig);
+            }
+            state.multisig = config.clone();
+            logger.log(&VersusEvent::Multisig(MultisigEvent {
+                config,
+            }))?;
+        }
+        ProposalAction::PayoutPrize(payout) => {
+            pay_prize(ctx.self_address(), host, payout, logger)?;
+        }
+        ProposalAction::SetGameServer(params) => {
+            state.set_game_server(params.public_key, params.enabled);
+            logger.log(&VersusEvent::GameServer(GameServerEvent {
+                public_key: params.public_key,
+                enabled: params.enabled,
+            }))?;
+        }
+        ProposalAction::GrantRole(params) => {
+            state.roles.insert((params.address, params.role));
+            logger.log(&VersusEvent::GrantRole(GrantRoleEvent {
+                address: params.address,
+                role: params.role,
+            }))?;
+        }
+        ProposalAction::CancelAdminTransfer => {
+            let pending_admin = state.pending_admin.take().ok_or(ContractError::NoPendingAdmin)?;
+            logger.log(&VersusEvent::AdminTransferCancelled(AdminTransferCancelledEvent {
+                pending_admin: pending_admin.address,
+            }))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads the version of the state layout and the admin from the state root.
+/// Every layout starts with the admin, after the version if versioned.
+fn read_state_header<S: HasStateApi>(state_api: &S) -> ParseResult<(u32, Address)> {
+    let mut root = state_api.lookup_entry(&[]).ok_or_else(ParseError::default)?;
+    let version = match StateVersion::deserial(&mut root) {
+        Ok(StateVersion(version)) => version,
+        Err(_) => {
+            root.seek(SeekFrom::Start(0)).map_err(|_| ParseError::default())?;
+            0
+        }
+    };
+    let admin = Address::deserial(&mut root)?;
+    Ok((version, admin))
+}
+
+/// Converts the state from the given version to `STATE_VERSION`, one version
+/// at a time. Each step reads the state root in the layout of its version and
+/// writes it in the layout of the next version.
+fn migrate_state<S: HasStateApi>(
+    state_api: &mut S,
+    state_builder: &mut StateBuilder<S>,
+    from: u32,
+    now: Timestamp,
+) -> ContractResult<()> {
+    ensure!(from != STATE_VERSION, ContractError::StateAlreadyMigrated);
+    ensure!(from < STATE_VERSION, ContractError::UnknownStateVersion);
+
+    for version in from..STATE_VERSION {
+        match version {
+            0 => migrate_v0_to_v1(state_api, state_builder, now)?,
+            _ => bail!(ContractError::UnknownStateVersion),
+        }
+    }
+    Ok(())
+}
+
+/// Converts the state from version 0 to version 1. Players keep their state
+/// and results, and get the initial rating and the time of the migration as
+/// registration time, since version 0 recorded neither. The admin gets the
+/// operational roles, as in a new instance.
+fn migrate_v0_to_v1<S: HasStateApi>(
+    state_api: &mut S,
+    state_builder: &mut StateBuilder<S>,
+    now: Timestamp,
+) -> ContractResult<()> {
+    let mut legacy: StateV0<S> = state_api.read_root()?;
+    let mut state = State::new(state_builder, legacy.admin);
+    state.paused = legacy.paused;
+    state.implementors = legacy.implementors;
+
+    let players: Vec<(Address, PlayerDataV0)> = legacy
+        .player_data
+        .iter()
+        .map(|(player, player_data)| (*player, (*player_data).clone()))
+        .collect();
+    let initial_rating = state.rating_engine.initial_rating();
+    for (player, player_data) in players {
+        legacy.player_data.remove(&player);
+        state.player_data.insert(player, PlayerData {
+            wins: player_data.wins,
+            losses: player_data.losses,
+            ..PlayerData::new(player_data.state, initial_rating, now)
+        });
+        state.player_count += 1;
+    }
+    state.rebuild_leaderboard();
+
+    state_api.write_root(&state);
+    Ok(())
+}
+
+// Contract functions
+
+/// Init function that creates a new smart contract.
+#[init(contract = "Versus-League-Manager", enable_logger, event = "VersusEvent")]
+fn contract_init<S: HasStateApi>(
+    ctx: &impl HasInitContext,
+    state_builder: &mut StateBuilder<S>,
+    logger: &mut impl HasLogger,
+) -> InitResult<State<S>> {
+    // Get the instantiator of this contract instance to be used as the initial
+    // admin.
+    let invoker = Address::Account(ctx.init_origin());
+    // Construct the initial contract state.
+    let state = State::new(state_builder, invoker);
+
+    logger.log(&VersusEvent::NewAdmin(NewAdminEvent {
+        new_admin: invoker,
+    }))?;
+
+    Ok(state)
+}
+
+/// Add new player.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "setPlayerData",
+    parameter = "(Address, PlayerState)",
+    error = "CustomContractError",
+    mutable,
+    enable_logger
+)]
+fn contract_state_set_player_data<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    // Check that contract is not paused.
+    ensure!(!host.state().paused, ContractError::ContractPaused);
+    // Check that only moderators are authorized to set player data.
+    ensure!(host.state().has_role(&ctx.sender(), Role::Moderator), ContractError::Unauthorized);
+
+    let params: (Address, PlayerState) = ctx.parameter_cursor().get()?;
+
+    set_player_data(
+        host.state_mut(),
+        &UpdatePlayerStateParams {
+            player: params.0,
+            state: params.1,
+        },
+        ctx.metadata().slot_time(),
+        logger,
+    )
+}
+
+/// Suspend a player until the given time. The player is reinstated to the
+/// state it had before automatically once the time has passed. Suspending a
+/// suspended player again replaces the end of the suspension. Setting the
+/// state of the player with `setPlayerData` ends the suspension early.
+///
+/// It rejects if:
+/// - The contract is paused.
+/// - Sender does not have the `Moderator` role.
+/// - It fails to parse the parameter.
+/// - The player has not been added.
+/// - The end of the suspension is not in the future.
+/// - In `Strict` mode, the player is already suspended.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "suspendPlayer",
+    parameter = "SuspendPlayerParams",
+    error = "CustomContractError",
+    mutable,
+    enable_logger
+)]
+fn contract_suspend_player<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    // Check that contract is not paused.
+    ensure!(!host.state().paused, ContractError::ContractPaused);
+    // Check that only moderators are authorized to suspend players.
+    ensure!(host.state().has_role(&ctx.sender(), Role::Moderator), ContractError::Unauthorized);
+
+    // Parse the parameter.
+    let params: SuspendPlayerParams = ctx.parameter_cursor().get()?;
+    ensure!(params.until > ctx.metadata().slot_time(), ContractError::InvalidExpiry);
+
+    let state = host.state_mut();
+    let mut player_data =
+        state.player_data.get_mut(&params.player).ok_or(ContractError::PlayerDoesNotExist)?;
+    let current_state = player_data.state;
+    if state.validation_mode == ValidationMode::Strict {
+        ensure!(
+            PlayerState::is_valid_transition(Some(current_state), PlayerState::Suspended),
+            ContractError::InvalidStateTransition
+        );
+    }
+    player_data.state = PlayerState::Suspended;
+    drop(player_data);
+    // A player suspended again keeps the state from before the first
+    // suspension.
+    let previous_state = match state.suspensions.get(&params.player) {
+        Some(suspension) => suspension.previous_state,
+        None => current_state,
+    };
+    state.suspensions.insert(params.player, Suspension {
+        until: params.until,
+        reason: params.reason,
+        moderator: ctx.sender(),
+        previous_state,
+    });
+
+    logger.log(&VersusEvent::PlayerSuspended(PlayerSuspendedEvent {
+        player: params.player,
+        until: params.until,
+        reason: params.reason,
+        moderator: ctx.sender(),
+    }))?;
+
+    Ok(())
+}
+
+/// Register the sender as a new player, paying the entry fee set by the
+/// admin. The fee is added to the treasury. The player starts as `Active`, or
+/// as `PendingApproval` if the registration settings require approval.
+///
+/// It rejects if:
+/// - The contract is paused.
+/// - Sender is not an account.
+/// - Registration is closed.
+/// - The transferred amount is not the entry fee.
+/// - Sender has already been added.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "register",
+    error = "CustomContractError",
+    payable,
+    mutable,
+    enable_logger
+)]
+fn contract_register<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    amount: Amount,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    // Check that contract is not paused.
+    ensure!(!host.state().paused, ContractError::ContractPaused);
+    // Check that the sender is an account.
+    let player = match ctx.sender() {
+        Address::Account(_) => ctx.sender(),
+        Address::Contract(_) => bail!(ContractError::OnlyAccount),
+    };
+
+    let state = host.state_mut();
+    // Check that the entry fee is paid.
+    let fee = match state.registration.fee {
+        RegistrationFee::Free => Amount::zero(),
+        RegistrationFee::Fixed(fee) => fee,
+        RegistrationFee::Closed => bail!(ContractError::RegistrationClosed),
+    };
+    ensure_eq!(amount, fee, ContractError::WrongAmount);
+    ensure!(state.player_data.get(&player).is_none(), ContractError::PlayerAlreadyExists);
+
+    let player_state = if state.registration.requires_approval {
+        PlayerState::PendingApproval
+    } else {
+        PlayerState::Active
+    };
+    let initial_rating = state.rating_engine.initial_rating();
+    let now = ctx.metadata().slot_time();
+    state.player_data.insert(player, PlayerData::new(player_state, initial_rating, now));
+    state.player_count += 1;
+    state.treasury += amount;
+
+    logger.log(&VersusEvent::PlayerRegistered(PlayerRegisteredEvent {
+        player,
+        state: player_state,
+    }))?;
+
+    Ok(())
+}
+
+/// Add new players or change the state of existing players in one call. Each
+/// player gets its own event.
+///
+/// It rejects if:
+/// - The contract is paused.
+/// - Sender does not have the `Moderator` role.
+/// - It fails to parse the parameter.
+/// - In `AllOrNothing` mode, any of the updates cannot be applied.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "setPlayerDataBatch",
+    parameter = "SetPlayerDataBatchParams",
+    return_value = "Vec<BatchItemOutcome>",
+    error = "CustomContractError",
+    mutable,
+    enable_logger
+)]
+fn contract_set_player_data_batch<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<Vec<BatchItemOutcome>> {
+    // Check that contract is not paused.
+    ensure!(!host.state().paused, ContractError::ContractPaused);
+    // Check that only moderators are authorized to set player data.
+    ensure!(host.state().has_role(&ctx.sender(), Role::Moderator), ContractError::Unauthorized);
+
+    // Parse the parameter.
+    let params: SetPlayerDataBatchParams = ctx.parameter_cursor().get()?;
+
+    let now = ctx.metadata().slot_time();
+    let state = host.state_mut();
+    apply_batch(params.mode, params.updates, |update| set_player_data(state, &update, now, logger))
+}
+
+/// Record the result of a battle for one player. In `Lenient` mode results
+/// for players that have not been added are ignored.
+///
+/// It rejects if:
+/// - The contract is paused.
+/// - Sender does not have the `Reporter` role.
+/// - It fails to parse the parameter.
+/// - The score does not agree with the result.
+/// - The player is suspended or waits for approval.
+/// - In `Strict` mode, the player has not been added.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "updateBattleResult",
+    parameter = "UpdateBattleResultParams",
+    error = "CustomContractError",
+    mutable,
+    enable_logger
+)]
+fn update_battle_result<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+
+    // Check that contract is not paused.
+    ensure!(!host.state().paused, ContractError::ContractPaused);
+    // Check that only reporters are authorized to update battle results.
+    ensure!(host.state().has_role(&ctx.sender(), Role::Reporter), ContractError::Unauthorized);
+
+    let params: UpdateBattleResultParams = ctx.parameter_cursor().get()?;
+
+    let mode = host.state().validation_mode;
+    match apply_battle_result(host.state_mut(), &params, ctx.metadata().slot_time(), logger) {
+        // Results for players that have not been added are ignored in
+        // `Lenient` mode.
+        Err(ContractError::PlayerDoesNotExist) if mode == ValidationMode::Lenient => Ok(()),
+        result => result,
+    }
+}
+
+/// Record many battle results in one call. Each result gets its own event.
+/// Unlike `updateBattleResult`, results for players that have not been added
+/// count as invalid items.
+///
+/// It rejects if:
+/// - The contract is paused.
+/// - Sender does not have the `Reporter` role.
+/// - It fails to parse the parameter.
+/// - In `AllOrNothing` mode, any of the results cannot be recorded.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "updateBattleResultBatch",
+    parameter = "UpdateBattleResultBatchParams",
+    return_value = "Vec<BatchItemOutcome>",
+    error = "CustomContractError",
+    mutable,
+    enable_logger
+)]
+fn contract_update_battle_result_batch<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<Vec<BatchItemOutcome>> {
+    // Check that contract is not paused.
+    ensure!(!host.state().paused, ContractError::ContractPaused);
+    // Check that only reporters are authorized to update battle results.
+    ensure!(host.state().has_role(&ctx.sender(), Role::Reporter), ContractError::Unauthorized);
+
+    // Parse the parameter.
+    let params: UpdateBattleResultBatchParams = ctx.parameter_cursor().get()?;
+
+    let now = ctx.metadata().slot_time();
+    let state = host.state_mut();
+    apply_batch(params.mode, params.updates, |update| {
+        apply_battle_result(state, &update, now, logger)
+    })
+}
+
+/// Report both results of a battle in one transaction. The ratings of both
+/// players are updated based on their ratings before the battle. If the
+/// players wagered on the battle, the challenge is settled and the pot is
+/// paid out.
+///
+/// It rejects if:
+/// - The contract is paused.
+/// - Sender does not have the `Reporter` role.
+/// - It fails to parse the parameter.
+/// - The winner and the loser are the same player.
+/// - The score does not agree with the result.
+/// - A battle with the same identifier has already been reported.
+/// - A result of the battle has been proposed and not yet finalized.
+/// - The winner or the loser has not been added.
+/// - The winner or the loser is suspended or waits for approval.
+/// - The challenge to settle has not been accepted or is for other players.
+/// - A payout of the challenge fails.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "reportMatch",
+    parameter = "ReportMatchParams",
+    error = "CustomContractError",
+    mutable,
+    enable_logger
+)]
+fn contract_report_match<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    // Check that contract is not paused.
+    ensure!(!host.state().paused, ContractError::ContractPaused);
+    // Check that only reporters are authorized to report battles.
+    ensure!(host.state().has_role(&ctx.sender(), Role::Reporter), ContractError::Unauthorized);
+
+    // Parse the parameter.
+    let params: ReportMatchParams = ctx.parameter_cursor().get()?;
+
+    report_match(host, params, ctx.metadata().slot_time(), false, logger)
+}
+
+/// Submit a battle result signed by a registered game server. Anyone, e.g.
+/// a relayer or one of the players, can submit the result. The result is
+/// then reported as with `reportMatch`.
+///
+/// It rejects if:
+/// - The contract is paused.
+/// - It fails to parse the parameter.
+/// - The game server is not registered or has been disabled.
+/// - The message is for another contract instance.
+/// - The message has expired.
+/// - The nonce is not the next nonce of the game server.
+/// - The signature is not valid.
+/// - Reporting the result fails, see `reportMatch`.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "submitSignedResult",
+    parameter = "SubmitSignedResultParams",
+    error = "CustomContractError",
+    crypto_primitives,
+    mutable,
+    enable_logger
+)]
+fn contract_submit_signed_result<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+    crypto_primitives: &impl HasCryptoPrimitives,
+) -> ContractResult<()> {
+    // Check that contract is not paused.
+    ensure!(!host.state().paused, ContractError::ContractPaused);
+
+    // Parse the parameter.
+    let params: SubmitSignedResultParams = ctx.parameter_cursor().get()?;
+    let message = params.message;
+    let now = ctx.metadata().slot_time();
+
+    {
+        let mut server = host
+            .state_mut()
+            .game_servers
+            .get_mut(&params.server)
+            .ok_or(ContractError::UnknownGameServer)?;
+        ensure!(server.enabled, ContractError::UnknownGameServer);
+        ensure_eq!(message.contract_address, ctx.self_address(), ContractError::WrongContract);
+        ensure!(now < message.expiry, ContractError::SignatureExpired);
+        ensure_eq!(message.nonce, server.nonce, ContractError::NonceMismatch);
+
+        // Check the signature of the game server.
+        let valid = crypto_primitives.verify_ed25519_signature(
+            params.server,
+            params.signature,
+            &to_bytes(&message),
+        );
+        ensure!(valid, ContractError::WrongSignature);
+
+        // Use up the nonce.
+        server.nonce += 1;
+    }
+
+    logger.log(&VersusEvent::SignedResult(SignedResultEvent {
+        server: params.server,
+        nonce: message.nonce,
+        battle_id: message.report.battle_id,
+    }))?;
+
+    report_match(host, message.report, now, false, logger)
+}
+
+/// Propose a battle result. The result changes the players' data only once it
+/// is finalized: when the losing side confirms it with `confirmResult`, or
+/// with `finalizeResult` after the dispute window if nobody disputed it with
+/// `disputeResult`.
+///
+/// It rejects if:
+/// - The contract is paused.
+/// - Sender does not have the `Reporter` role.
+/// - It fails to parse the parameter.
+/// - The winner and the loser are the same player.
+/// - The score does not agree with the result.
+/// - The battle has already been reported or a result has been proposed.
+/// - The winner or the loser has not been added.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "proposeResult",
+    parameter = "ReportMatchParams",
+    error = "CustomContractError",
+    mutable,
+    enable_logger
+)]
+fn contract_propose_result<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    // Check that contract is not paused.
+    ensure!(!host.state().paused, ContractError::ContractPaused);
+    // Check that only reporters are authorized to propose results.
+    ensure!(host.state().has_role(&ctx.sender(), Role::Reporter), ContractError::Unauthorized);
+
+    // Parse the parameter.
+    let params: ReportMatchParams = ctx.parameter_cursor().get()?;
+    params.winner_result()?;
+
+    let state = host.state_mut();
+    ensure!(state.battles.get(&params.battle_id).is_none(), ContractError::BattleAlreadyReported);
+    ensure!(
+        state.pending_results.get(&params.battle_id).is_none(),
+        ContractError::ResultAlreadyProposed
+    );
+    ensure!(
+        state.player_data.get(&params.winner).is_some()
+            && state.player_data.get(&params.loser).is_some(),
+        ContractError::PlayerDoesNotExist
+    );
+
+    let dispute_deadline = ctx
+        .metadata()
+        .slot_time()
+        .checked_add(state.dispute_window)
+        .ok_or(ContractError::InvalidExpiry)?;
+    logger.log(&VersusEvent::ResultProposed(ResultProposedEvent {
+        battle_id: params.battle_id,
+        winner: params.winner,
+        loser: params.loser,
+        draw: params.draw,
+        dispute_deadline,
+    }))?;
+    state.pending_results.insert(params.battle_id, PendingResult {
+        report: params,
+        proposer: ctx.sender(),
+        dispute_deadline,
+        status: PendingResultStatus::Proposed,
+    });
+
+    Ok(())
+}
+
+/// Confirm a proposed result as the losing side, or either player of a draw.
+/// The result is finalized right away.
+///
+/// It rejects if:
+/// - The contract is paused.
+/// - It fails to parse the parameter.
+/// - No result has been proposed for the battle.
+/// - Sender is not the losing side.
+/// - The result has been disputed.
+/// - Reporting the result fails, see `reportMatch`.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "confirmResult",
+    parameter = "BattleId",
+    error = "CustomContractError",
+    mutable,
+    enable_logger
+)]
+fn contract_confirm_result<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    // Check that contract is not paused.
+    ensure!(!host.state().paused, ContractError::ContractPaused);
+
+    // Parse the parameter.
+    let battle_id: BattleId = ctx.parameter_cursor().get()?;
+
+    let pending_result = host.state().get_pending_result(battle_id)?;
+    ensure!(pending_result.report.is_losing_side(&ctx.sender()), ContractError::Unauthorized);
+    ensure_eq!(
+        pending_result.status,
+        PendingResultStatus::Proposed,
+        ContractError::InvalidResultStatus
+    );
+    host.state_mut().pending_results.remove(&battle_id);
+
+    logger.log(&VersusEvent::ResultConfirmed(ResultConfirmedEvent {
+        battle_id,
+        player: ctx.sender(),
+    }))?;
+
+    finalize_result(host, pending_result, ctx.metadata().slot_time(), false, logger)
+}
+
+/// Dispute a result as the losing side, or either player of a draw, with the
+/// hash of the evidence of the sender. A proposed result can be disputed
+/// until its dispute deadline and is then not finalized. A reported result
+/// can be disputed within the dispute window after it was reported, unless
+/// it settled a challenge. The dispute is resolved by an arbitrator with
+/// `resolveDispute`.
+///
+/// It rejects if:
+/// - The contract is paused.
+/// - It fails to parse the parameter.
+/// - The result has already been disputed.
+/// - No result has been proposed or reported for the battle.
+/// - Sender is not the losing side.
+/// - The reported battle settled a challenge, since the pot has been paid out.
+/// - The dispute deadline has passed.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "disputeResult",
+    parameter = "DisputeParams",
+    error = "CustomContractError",
+    mutable,
+    enable_logger
+)]
+fn contract_dispute_result<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    // Check that contract is not paused.
+    ensure!(!host.state().paused, ContractError::ContractPaused);
+
+    // Parse the parameter.
+    let params: DisputeParams = ctx.parameter_cursor().get()?;
+
+    let sender = ctx.sender();
+    let now = ctx.metadata().slot_time();
+    let state = host.state_mut();
+    ensure!(state.disputes.get(&params.battle_id).is_none(), ContractError::AlreadyDisputed);
+
+    let (winner, loser) = match state.pending_results.get_mut(&params.battle_id) {
+        Some(mut pending_result) => {
+            ensure!(pending_result.report.is_losing_side(&sender), ContractError::Unauthorized);
+            ensure!(now < pending_result.dispute_deadline, ContractError::DisputeWindowClosed);
+            pending_result.status = PendingResultStatus::Disputed;
+            (pending_result.report.winner, pending_result.report.loser)
+        }
+        None => {
+            let battle =
+                state.battles.get(&params.battle_id).ok_or(ContractError::BattleDoesNotExist)?;
+            ensure!(
+                is_losing_side(&battle.winner, &battle.loser, battle.draw, &sender),
+                ContractError::Unauthorized
+            );
+            ensure!(battle.challenge.is_none(), ContractError::ChallengeSettled);
+            if let Some(dispute_deadline) = battle.reported_at.checked_add(state.dispute_window) {
+                ensure!(now < dispute_deadline, ContractError::DisputeWindowClosed);
+            }
+            // The change of the battle is pruned once the window has closed,
+            // which may have been shorter at the time.
+            ensure!(
+                state.battle_changes.get(&params.battle_id).is_some(),
+                ContractError::DisputeWindowClosed
+            );
+            (battle.winner, battle.loser)
+        }
+    };
+
+    let mut dispute = Dispute {
+        winner,
+        loser,
+        opened_by: sender,
+        winner_evidence: None,
+        loser_evidence: None,
+        status: DisputeStatus::Open,
+    };
+    dispute.set_evidence(&sender, params.evidence)?;
+    state.disputes.insert(params.battle_id, dispute);
+
+    logger.log(&VersusEvent::ResultDisputed(ResultDisputedEvent {
+        battle_id: params.battle_id,
+        player: sender,
+        evidence: params.evidence,
+    }))?;
+
+    Ok(())
+}
+
+/// Submit the hash of the evidence of one of the players for an open
+/// dispute. Evidence submitted earlier by the same player is replaced.
+///
+/// It rejects if:
+/// - The contract is paused.
+/// - It fails to parse the parameter.
+/// - The battle has not been disputed.
+/// - The dispute has been resolved.
+/// - Sender is not one of the players.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "submitEvidence",
+    parameter = "DisputeParams",
+    error = "CustomContractError",
+    mutable,
+    enable_logger
+)]
+fn contract_submit_evidence<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    // Check that contract is not paused.
+    ensure!(!host.state().paused, ContractError::ContractPaused);
+
+    // Parse the parameter.
+    let params: DisputeParams = ctx.parameter_cursor().get()?;
+
+    let mut dispute = host
+        .state_mut()
+        .disputes
+        .get_mut(&params.battle_id)
+        .ok_or(ContractError::DisputeDoesNotExist)?;
+    ensure_eq!(dispute.status, DisputeStatus::Open, ContractError::InvalidDisputeStatus);
+    dispute.set_evidence(&ctx.sender(), params.evidence)?;
+
+    logger.log(&VersusEvent::EvidenceSubmitted(EvidenceSubmittedEvent {
+        battle_id: params.battle_id,
+        player: ctx.sender(),
+        evidence: params.evidence,
+    }))?;
+
+    Ok(())
+}
+
+/// Resolve an open dispute. Upholding a proposed result finalizes it as
+/// proposed, overturning it finalizes the reversed result and voiding it
+/// records it as voided. Overturning a reported result rolls back what the battle
+/// changed and records the reversed result, and voiding it rolls the battle
+/// back and marks its record as voided.
+///
+/// It rejects if:
+/// - The contract is paused.
+/// - Sender does not have the `Arbitrator` role.
+/// - It fails to parse the parameter.
+/// - The battle has not been disputed.
+/// - The dispute has already been resolved.
+/// - The result is a draw and the decision is to overturn it.
+/// - Reporting a proposed result fails, see `reportMatch`. Suspended players
+///   and players waiting for approval do not make it fail.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "resolveDispute",
+    parameter = "ResolveDisputeParams",
+    error = "CustomContractError",
+    mutable,
+    enable_logger
+)]
+fn contract_resolve_dispute<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    // Check that contract is not paused.
+    ensure!(!host.state().paused, ContractError::ContractPaused);
+    // Check that only arbitrators are authorized to resolve disputes.
+    ensure!(host.state().has_role(&ctx.sender(), Role::Arbitrator), ContractError::Unauthorized);
+
+    // Parse the parameter.
+    let params: ResolveDisputeParams = ctx.parameter_cursor().get()?;
+    let battle_id = params.battle_id;
+
+    let now = ctx.metadata().slot_time();
+    let state = host.state_mut();
+    {
+        let mut dispute =
+            state.disputes.get_mut(&battle_id).ok_or(ContractError::DisputeDoesNotExist)?;
+        ensure_eq!(dispute.status, DisputeStatus::Open, ContractError::InvalidDisputeStatus);
+        dispute.status = match params.decision {
+            DisputeDecision::Uphold => DisputeStatus::Upheld,
+            DisputeDecision::Overturn => DisputeStatus::Overturned,
+            DisputeDecision::Void => DisputeStatus::Voided,
+        };
+    }
+
+    logger.log(&VersusEvent::DisputeResolved(DisputeResolvedEvent {
+        battle_id,
+        arbitrator: ctx.sender(),
+        decision: params.decision,
+    }))?;
+
+    let pending_result =
+        state.pending_results.get(&battle_id).map(|pending_result| (*pending_result).clone());
+    match pending_result {
+        // The result was disputed before it was finalized.
+        Some(pending_result) => {
+            state.pending_results.remove(&battle_id);
+            match params.decision {
+                DisputeDecision::Uphold => {
+                    finalize_result(host, pending_result, now, true, logger)
+                }
+                DisputeDecision::Overturn => {
+                    let report = pending_result.report.overturned()?;
+                    let pending_result = PendingResult {
+                        report,
+                        ..pending_result
+                    };
+                    finalize_result(host, pending_result, now, true, logger)
+                }
+                DisputeDecision::Void => {
+                    // Keep the voided result, so that the battle id cannot be
+                    // used for another result.
+                    let report = pending_result.report;
+                    state.battles.insert(battle_id, BattleRecord {
+                        winner: report.winner,
+                        loser: report.loser,
+                        draw: report.draw,
+                        score: report.score,
+                        challenge: None,
+                        reported_at: now,
+                        voided: true,
+                    });
+                    Ok(())
+                }
+            }
+        }
+        // The result was disputed after it was reported.
+        None => match params.decision {
+            DisputeDecision::Uphold => {
+                // The battle can no longer be disputed, so its change is not needed.
+                state.battle_changes.remove(&battle_id);
+                Ok(())
+            }
+            DisputeDecision::Overturn => state.overturn_battle(battle_id, now),
+            DisputeDecision::Void => {
+                state.revert_battle(battle_id)?;
+                if let Some(mut battle) = state.battles.get_mut(&battle_id) {
+                    battle.voided = true;
+                }
+                Ok(())
+            }
+        },
+    }
+}
+
+/// Finalize a proposed result that nobody disputed once the dispute deadline
+/// has passed. Anyone can finalize the result.
+///
+/// It rejects if:
+/// - The contract is paused.
+/// - It fails to parse the parameter.
+/// - No result has been proposed for the battle.
+/// - The result has been disputed.
+/// - The dispute deadline has not passed.
+/// - Reporting the result fails, see `reportMatch`.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "finalizeResult",
+    parameter = "BattleId",
+    error = "CustomContractError",
+    mutable,
+    enable_logger
+)]
+fn contract_finalize_result<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    // Check that contract is not paused.
+    ensure!(!host.state().paused, ContractError::ContractPaused);
+
+    // Parse the parameter.
+    let battle_id: BattleId = ctx.parameter_cursor().get()?;
+
+    let now = ctx.metadata().slot_time();
+    let pending_result = host.state().get_pending_result(battle_id)?;
+    ensure_eq!(
+        pending_result.status,
+        PendingResultStatus::Proposed,
+        ContractError::InvalidResultStatus
+    );
+    ensure!(now >= pending_result.dispute_deadline, ContractError::DisputeWindowOpen);
+    host.state_mut().pending_results.remove(&battle_id);
+
+    finalize_result(host, pending_result, now, false, logger)
+}
+
+/// Remove what reported battles changed once they can no longer be disputed,
+/// which frees the storage kept for rolling them back. Anyone can prune.
+/// Battles that can still be disputed or that have been disputed are
+/// skipped.
+///
+/// It rejects if:
+/// - The contract is paused.
+/// - It fails to parse the parameter.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "pruneBattleChanges",
+    parameter = "Vec<BattleId>",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_prune_battle_changes<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<()> {
+    // Check that contract is not paused.
+    ensure!(!host.state().paused, ContractError::ContractPaused);
+
+    // Parse the parameter.
+    let battle_ids: Vec<BattleId> = ctx.parameter_cursor().get()?;
+
+    let now = ctx.metadata().slot_time();
+    let state = host.state_mut();
+    for battle_id in battle_ids {
+        let reported_at = match state.battles.get(&battle_id) {
+            Some(battle) => battle.reported_at,
+            None => continue,
+        };
+        match reported_at.checked_add(state.dispute_window) {
+            Some(dispute_deadline) if now >= dispute_deadline => {}
+            _ => continue,
+        }
+        // An open dispute still needs the change.
+        if state.disputes.get(&battle_id).is_some() {
+            continue;
+        }
+        state.battle_changes.remove(&battle_id);
+    }
+
+    Ok(())
+}
+
+/// Challenge another player to a battle with a CCD stake. The transferred
+/// amount is the stake, which both players lock until the battle is settled
+/// through `reportMatch` or the challenge is refunded. Returns the id of the
+/// new challenge.
+///
+/// It rejects if:
+/// - The contract is paused.
+/// - Sender is not an account.
+/// - It fails to parse the parameter.
+/// - The stake is zero.
+/// - Sender challenges itself.
+/// - The expiry is not in the future.
+/// - Sender or the opponent has not been added or is not active.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "createChallenge",
+    parameter = "CreateChallengeParams",
+    return_value = "ChallengeId",
+    error = "CustomContractError",
+    payable,
+    mutable,
+    enable_logger
+)]
+fn contract_create_challenge<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    amount: Amount,
+    logger: &mut impl HasLogger,
+) -> ContractResult<ChallengeId> {
+    // Check that contract is not paused.
+    ensure!(!host.state().paused, ContractError::ContractPaused);
+    // Check that the sender is an account.
+    let challenger = match ctx.sender() {
+        Address::Account(account) => account,
+        Address::Contract(_) => bail!(ContractError::OnlyAccount),
+    };
+
+    // Parse the parameter.
+    let params: CreateChallengeParams = ctx.parameter_cursor().get()?;
+    ensure!(amount > Amount::zero(), ContractError::WrongAmount);
+    ensure!(challenger != params.opponent, ContractError::InvalidMatch);
+    let now = ctx.metadata().slot_time();
+    ensure!(params.expiry > now, ContractError::InvalidExpiry);
+
+    // Check that both players can wager.
+    let state = host.state_mut();
+    reinstate_if_expired(state, &Address::Account(challenger), now, logger)?;
+    reinstate_if_expired(state, &Address::Account(params.opponent), now, logger)?;
+    state.ensure_active(&Address::Account(challenger))?;
+    state.ensure_active(&Address::Account(params.opponent))?;
+
+    let challenge = state.next_challenge_id;
+    state.next_challenge_id += 1;
+    state.challenges.insert(challenge, Challenge {
+        challenger,
+        opponent: params.opponent,
+        stake: amount,
+        expiry: params.expiry,
+        status: ChallengeStatus::Open,
+    });
+
+    logger.log(&VersusEvent::ChallengeCreated(ChallengeCreatedEvent {
+        challenge,
+        challenger,
+        opponent: params.opponent,
+        stake: amount,
+        expiry: params.expiry,
+    }))?;
+
+    Ok(challenge)
+}
+
+/// Accept a challenge by locking the same stake as the challenger.
+///
+/// It rejects if:
+/// - The contract is paused.
+/// - It fails to parse the parameter.
+/// - The challenge does not exist or is not open.
+/// - Sender is not the challenged player.
+/// - The challenge has expired.
+/// - The transferred amount is not the stake of the challenge.
+/// - Sender is not active.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "acceptChallenge",
+    parameter = "ChallengeId",
+    error = "CustomContractError",
+    payable,
+    mutable,
+    enable_logger
+)]
+fn contract_accept_challenge<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    amount: Amount,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    // Check that contract is not paused.
+    ensure!(!host.state().paused, ContractError::ContractPaused);
+
+    // Parse the parameter.
+    let challenge_id: ChallengeId = ctx.parameter_cursor().get()?;
+
+    let now = ctx.metadata().slot_time();
+    let state = host.state_mut();
+    reinstate_if_expired(state, &ctx.sender(), now, logger)?;
+    state.ensure_active(&ctx.sender())?;
+    let mut challenge =
+        state.challenges.get_mut(&challenge_id).ok_or(ContractError::ChallengeDoesNotExist)?;
+    ensure_eq!(challenge.status, ChallengeStatus::Open, ContractError::InvalidChallengeStatus);
+    ensure_eq!(
+        ctx.sender(),
+        Address::Account(challenge.opponent),
+        ContractError::Unauthorized
+    );
+    ensure!(now < challenge.expiry, ContractError::ChallengeExpired);
+    ensure_eq!(amount, challenge.stake, ContractError::WrongAmount);
+    challenge.status = ChallengeStatus::Accepted;
+    drop(challenge);
+
+    logger.log(&VersusEvent::ChallengeAccepted(ChallengeAcceptedEvent {
+        challenge: challenge_id,
+    }))?;
+
+    Ok(())
+}
+
+/// Refund the stakes of a challenge that has not been settled. The
+/// challenger can withdraw an open challenge at any time; otherwise anyone can
+/// trigger the refund once the challenge has expired.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+/// - The challenge does not exist or has already been settled or refunded.
+/// - The challenge has not expired and sender is not the challenger of an
+///   open challenge.
+/// - A refund fails.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "refundChallenge",
+    parameter = "ChallengeId",
+    error = "CustomContractError",
+    mutable,
+    enable_logger
+)]
+fn contract_refund_challenge<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    // Parse the parameter.
+    let challenge_id: ChallengeId = ctx.parameter_cursor().get()?;
+
+    let mut challenge = host
+        .state_mut()
+        .challenges
+        .get_mut(&challenge_id)
+        .ok_or(ContractError::ChallengeDoesNotExist)?;
+    let expired = ctx.metadata().slot_time() >= challenge.expiry;
+    let mut refunds = vec![(challenge.challenger, challenge.stake)];
+    match challenge.status {
+        ChallengeStatus::Open => {
+            ensure!(
+                expired || ctx.sender() == Address::Account(challenge.challenger),
+                ContractError::ChallengeNotExpired
+            );
+        }
+        ChallengeStatus::Accepted => {
+            ensure!(expired, ContractError::ChallengeNotExpired);
+            refunds.push((challenge.opponent, challenge.stake));
+        }
+        ChallengeStatus::Settled | ChallengeStatus::Refunded => {
+            bail!(ContractError::InvalidChallengeStatus)
+        }
+    }
+    challenge.status = ChallengeStatus::Refunded;
+    drop(challenge);
+
+    for (account, amount) in refunds {
+        host.invoke_transfer(&account, amount)?;
+    }
+
+    logger.log(&VersusEvent::ChallengeRefunded(ChallengeRefundedEvent {
+        challenge: challenge_id,
+    }))?;
+
+    Ok(())
+}
+
+/// Get paused.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "getPaused",
+    return_value = "bool",
+    error = "CustomContractError"
+)]
+fn contract_state_get_paused<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<bool> {
+    Ok(host.state().paused)
+}
+
+/// Get the state of a player. A player whose suspension has ended is
+/// reported in its reinstated state.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "getPlayerData",
+    parameter = "Address",
+    return_value = "(PlayerState)",
+    error = "CustomContractError"
+)]
+fn contract_state_get_player_data<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<PlayerState> {
+    let params: Address = ctx.parameter_cursor().get()?;
+
+    let state = host.state();
+    let player = state.player_data.get(&params);
+    match player {
+        Some(player) => {
+            Ok(state.effective_state(&params, player.state, ctx.metadata().slot_time()))
+        }
+        None => Err(CustomContractError::PlayerDoesNotExist.into()),
+    }
+}
+
+/// Get a page of players with their data, optionally only players in a given
+/// state. Pages follow the storage order of the players, and the cursor is
+/// the last player of the previous page, so pages stay consistent when
+/// players are added between calls. Players whose suspension has ended are
+/// listed and filtered in their reinstated state.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+/// - The page size is zero.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "getPlayers",
+    parameter = "GetPlayersParams",
+    return_value = "PlayersPage",
+    error = "CustomContractError"
+)]
+fn contract_get_players<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<PlayersPage> {
+    let params: GetPlayersParams = ctx.parameter_cursor().get()?;
+    ensure!(params.limit > 0, ContractError::InvalidPageSize);
+    let limit = params.limit.min(MAX_PLAYERS_PAGE_SIZE) as usize;
+    let now = ctx.metadata().slot_time();
+
+    // Players are stored in the order of their serialized addresses.
+    let cursor = params.cursor.map(|cursor| to_bytes(&cursor));
+
+    let state = host.state();
+    let mut players: Vec<PlayerEntry> = Vec::new();
+    let mut next_cursor = None;
+    for (player, data) in state.player_data.iter() {
+        if let Some(cursor) = &cursor {
+            if to_bytes(&*player) <= *cursor {
+                continue;
+            }
+        }
+        if players.len() == limit {
+            next_cursor = players.last().map(|entry| entry.player);
+            break;
+        }
+        let player_state = state.effective_state(&player, data.state, now);
+        if let Some(filter_state) = params.state {
+            if player_state != filter_state {
+                continue;
+            }
+        }
+        players.push(PlayerEntry {
+            player: *player,
+            data: PlayerData {
+                state: player_state,
+                ..(*data).clone()
+            },
+        });
+    }
+
+    Ok(PlayersPage {
+        players,
+        next_cursor,
+        player_count: state.player_count,
+    })
+}
+
+/// Get every stat of a player together with derived values such as the
+/// number of games played and the win rate. A player whose suspension has
+/// ended is reported in its reinstated state.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "getPlayerStats",
+    parameter = "Address",
+    return_value = "PlayerStats",
+    error = "CustomContractError"
+)]
+fn contract_get_player_stats<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<PlayerStats> {
+    let player: Address = ctx.parameter_cursor().get()?;
+
+    let state = host.state();
+    let player_data = state.player_data.get(&player);
+    match player_data {
+        Some(player_data) => Ok(PlayerStats {
+            state: state.effective_state(&player, player_data.state, ctx.metadata().slot_time()),
+            ..player_data.stats()
+        }),
+        None => Err(CustomContractError::PlayerDoesNotExist),
+    }
+}
+
+/// Get the timed suspension of a player with the time left until the player
+/// is reinstated. Returns `None` if the player is not on a timed suspension or
+/// the suspension has ended.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "getSuspension",
+    parameter = "Address",
+    return_value = "Option<SuspensionStatus>",
+    error = "CustomContractError"
+)]
+fn contract_get_suspension<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<Option<SuspensionStatus>> {
+    let player: Address = ctx.parameter_cursor().get()?;
+    let now = ctx.metadata().slot_time();
+
+    let suspension = match host.state().suspensions.get(&player) {
+        Some(suspension) => *suspension,
+        None => return Ok(None),
+    };
+    let remaining = suspension.until.duration_since(now).filter(|remaining| remaining.millis() > 0);
+    Ok(remaining.map(|remaining| SuspensionStatus {
+        suspension,
+        remaining,
+    }))
+}
+
+/// Get a game server, including the nonce of its next signed result.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "getGameServer",
+    parameter = "PublicKeyEd25519",
+    return_value = "Option<GameServer>",
+    error = "CustomContractError"
+)]
+fn contract_get_game_server<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<Option<GameServer>> {
+    let public_key: PublicKeyEd25519 = ctx.parameter_cursor().get()?;
+
+    Ok(host.state().game_servers.get(&public_key).map(|server| *server))
+}
+
+/// Get the scheduled upgrade, if any.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "getPendingUpgrade",
+    return_value = "Option<UpgradeParams>",
+    error = "CustomContractError"
+)]
+fn contract_get_pending_upgrade<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<Option<UpgradeParams>> {
+    Ok(host.state().pending_upgrade.clone())
+}
+
+/// Get a multisig proposal.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "getProposal",
+    parameter = "ProposalId",
+    return_value = "Proposal",
+    error = "CustomContractError"
+)]
+fn contract_get_proposal<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<Proposal> {
+    let proposal_id: ProposalId = ctx.parameter_cursor().get()?;
+
+    let proposal = host.state().proposals.get(&proposal_id);
+    match proposal {
+        Some(proposal) => Ok((*proposal).clone()),
+        None => Err(CustomContractError::ProposalDoesNotExist),
+    }
+}
+
+/// Get a proposed result that has not been finalized.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "getPendingResult",
+    parameter = "BattleId",
+    return_value = "PendingResult",
+    error = "CustomContractError"
+)]
+fn contract_get_pending_result<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<PendingResult> {
+    let battle_id: BattleId = ctx.parameter_cursor().get()?;
+
+    host.state().get_pending_result(battle_id)
+}
+
+/// Get the dispute about the result of a battle.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "getDispute",
+    parameter = "BattleId",
+    return_value = "Dispute",
+    error = "CustomContractError"
+)]
+fn contract_get_dispute<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<Dispute> {
+    let battle_id: BattleId = ctx.parameter_cursor().get()?;
+
+    let dispute = host.state().disputes.get(&battle_id);
+    match dispute {
+        Some(dispute) => Ok((*dispute).clone()),
+        None => Err(CustomContractError::DisputeDoesNotExist),
+    }
+}
+
+/// Get the record of a reported battle.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "getBattle",
+    parameter = "BattleId",
+    return_value = "BattleRecord",
+    error = "CustomContractError"
+)]
+fn contract_get_battle<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<BattleRecord> {
+    let battle_id: BattleId = ctx.parameter_cursor().get()?;
+
+    let battle = host.state().battles.get(&battle_id);
+    match battle {
+        Some(battle) => Ok((*battle).clone()),
+        None => Err(CustomContractError::BattleDoesNotExist),
+    }
+}
+
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "isAdded",
+    parameter = "Address",
+    return_value = "bool",
+    error = "CustomContractError"
+)]
+fn contract_state_is_added<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<bool> {
+    let params: Address = ctx.parameter_cursor().get()?;
+    let player_data = host.state().player_data.get(&params);
+
+    Ok(player_data.is_some())
+}
+
+/// Get a challenge.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "getChallenge",
+    parameter = "ChallengeId",
+    return_value = "Challenge",
+    error = "CustomContractError"
+)]
+fn contract_get_challenge<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<Challenge> {
+    let challenge_id: ChallengeId = ctx.parameter_cursor().get()?;
+
+    let challenge = host.state().challenges.get(&challenge_id);
+    match challenge {
+        Some(challenge) => Ok((*challenge).clone()),
+        None => Err(CustomContractError::ChallengeDoesNotExist),
+    }
+}
+
+/// Get the leaderboard with the ranks, addresses and lifetime data of the best
+/// players.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "getLeaderboard",
+    return_value = "Vec<LeaderboardPosition>",
+    error = "CustomContractError"
+)]
+fn contract_get_leaderboard<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<Vec<LeaderboardPosition>> {
+    let state = host.state();
+    let leaderboard = state.leaderboard.get();
+    let mut positions = Vec::with_capacity(leaderboard.len());
+    for (index, entry) in leaderboard.iter().enumerate() {
+        let stats = state.get_player(&entry.player).ok_or(ContractError::PlayerDoesNotExist)?;
+        positions.push(LeaderboardPosition {
+            rank: index as u32 + 1,
+            player: entry.player,
+            stats,
+        });
+    }
+    Ok(positions)
+}
+
+/// Get the token balance of a prize pool.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "getPrizePool",
+    parameter = "PrizePoolKey",
+    return_value = "ContractTokenAmount",
+    error = "CustomContractError"
+)]
+fn contract_get_prize_pool<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<ContractTokenAmount> {
+    let key: PrizePoolKey = ctx.parameter_cursor().get()?;
+
+    let balance = host.state().prize_pools.get(&key).map(|balance| *balance);
+    Ok(balance.unwrap_or_default())
+}
+
+/// Get the information of a season.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "getSeason",
+    parameter = "SeasonId",
+    return_value = "SeasonInfo",
+    error = "CustomContractError"
+)]
+fn contract_get_season<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<SeasonInfo> {
+    let season: SeasonId = ctx.parameter_cursor().get()?;
+
+    let info = host.state().seasons.get(&season);
+    match info {
+        Some(info) => Ok((*info).clone()),
+        None => Err(CustomContractError::SeasonDoesNotExist),
+    }
+}
+
+/// Get the stats of a player in a season, including ended seasons.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "getSeasonPlayerData",
+    parameter = "(SeasonId, Address)",
+    return_value = "PlayerData",
+    error = "CustomContractError"
+)]
+fn contract_get_season_player_data<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<PlayerData> {
+    let (season, player): (SeasonId, Address) = ctx.parameter_cursor().get()?;
+    ensure!(host.state().seasons.get(&season).is_some(), ContractError::SeasonDoesNotExist);
+
+    host.state().get_season_player(season, &player).ok_or(ContractError::PlayerDoesNotExist)
+}
+
+/// Check whether an address holds a role.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "hasRole",
+    parameter = "RoleParams",
+    return_value = "bool",
+    error = "CustomContractError"
+)]
+fn contract_has_role<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<bool> {
+    let params: RoleParams = ctx.parameter_cursor().get()?;
+
+    Ok(host.state().has_role(&params.address, params.role))
+}
+
+/// Get the supported standards or addresses of implementations of a list of
+/// standards.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "supports",
+    parameter = "SupportsQueryParams",
+    return_value = "SupportsQueryResponse",
+    error = "CustomContractError"
+)]
+fn contract_supports<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<SupportsQueryResponse> {
+    // Parse the parameter.
+    let params: SupportsQueryParams = ctx.parameter_cursor().get()?;
+
+    // Build the response.
+    let mut response = Vec::with_capacity(params.queries.len());
+    for std_id in params.queries {
+        if SUPPORTS_STANDARDS.contains(&std_id.as_standard_identifier()) {
+            response.push(SupportResult::Support);
+        } else {
+            response.push(host.state().have_implementors(&std_id));
+        }
+    }
+
+    Ok(SupportsQueryResponse::from(response))
+}
+
+/// Function to view state of the state contract.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "view",
+    return_value = "ReturnBasicState",
+    error = "CustomContractError"
+)]
+fn contract_view<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<ReturnBasicState> {
+    let state = ReturnBasicState {
+        version: host.state().version.0,
+        admin: host.state().admin,
+        pending_admin: host.state().pending_admin,
+        multisig: host.state().multisig.clone(),
+        paused: host.state().paused,
+        rating_engine: host.state().rating_engine,
+        current_season: host.state().current_season,
+        registration: host.state().registration,
+        treasury: host.state().treasury,
+        wager_fee: host.state().wager_fee,
+        leaderboard: host.state().leaderboard_config,
+        streak_milestones: host.state().streak_milestones.clone(),
+        validation_mode: host.state().validation_mode,
+        dispute_window: host.state().dispute_window,
+    };
+    Ok(state)
+}
+
+/// Propose a new admin of the contract instance. The admin only changes once
+/// the proposed address accepts with `acceptAdmin`. A new proposal replaces
+/// the pending one.
+///
+/// It rejects if:
+/// - Sender is not the admin of the contract instance.
+/// - It fails to parse the parameter.
+/// - The expiry is not in the future.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "proposeAdmin",
+    parameter = "ProposeAdminParams",
+    error = "ContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_propose_admin<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    // Check that only the current admin is authorized to propose a new admin.
+    ensure_eq!(ctx.sender(), host.state().admin, ContractError::Unauthorized);
+    ensure!(host.state().multisig.is_none(), ContractError::MultisigRequired);
+
+    // Parse the parameter.
+    let params: ProposeAdminParams = ctx.parameter_cursor().get()?;
+    if let Some(expiry) = params.expiry {
+        ensure!(expiry > ctx.metadata().slot_time(), ContractError::InvalidExpiry);
+    }
+
+    // Store the proposed admin.
+    host.state_mut().pending_admin = Some(PendingAdmin {
+        address: params.new_admin,
+        expiry: params.expiry,
+    });
+
+    logger.log(&VersusEvent::AdminProposed(AdminProposedEvent {
+        new_admin: params.new_admin,
+        expiry: params.expiry,
+    }))?;
+
+    Ok(())
+}
+
+/// Accept the proposed admin transfer. The sender becomes the admin of the
+/// contract instance, and the operational roles of the previous admin are
+/// revoked.
+///
+/// It rejects if:
+/// - No admin transfer has been proposed.
+/// - Sender is not the proposed admin.
+/// - The proposal has expired.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "acceptAdmin",
+    error = "ContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_accept_admin<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    let pending_admin = host.state().pending_admin.ok_or(ContractError::NoPendingAdmin)?;
+    // Check that only the proposed admin is authorized to accept.
+    ensure_eq!(ctx.sender(), pending_admin.address, ContractError::Unauthorized);
+    if let Some(expiry) = pending_admin.expiry {
+        ensure!(ctx.metadata().slot_time() < expiry, ContractError::AdminTransferExpired);
+    }
+
+    // Update the admin variable.
+    let state = host.state_mut();
+    let previous_admin = state.admin;
+    state.admin = pending_admin.address;
+    state.pending_admin = None;
+
+    logger.log(&VersusEvent::NewAdmin(NewAdminEvent {
+        new_admin: pending_admin.address,
+    }))?;
+
+    // Revoke the roles the previous admin held for operating the league.
+    if previous_admin != pending_admin.address {
+        for role in OPERATIONAL_ROLES {
+            if state.roles.remove(&(previous_admin, role)) {
+                logger.log(&VersusEvent::RevokeRole(RevokeRoleEvent {
+                    address: previous_admin,
+                    role,
+                }))?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Cancel the proposed admin transfer.
+///
+/// It rejects if:
+/// - Sender is not the admin of the contract instance.
+/// - No admin transfer has been proposed.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "cancelAdminTransfer",
+    error = "ContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_cancel_admin_transfer<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    // Check that only the current admin is authorized to cancel the transfer.
+    ensure_eq!(ctx.sender(), host.state().admin, ContractError::Unauthorized);
+    ensure!(host.state().multisig.is_none(), ContractError::MultisigRequired);
+
+    let pending_admin = host.state_mut().pending_admin.take().ok_or(ContractError::NoPendingAdmin)?;
+
+    logger.log(&VersusEvent::AdminTransferCancelled(AdminTransferCancelledEvent {
+        pending_admin: pending_admin.address,
+    }))?;
+
+    Ok(())
+}
+
+/// Pause or unpause the contract. Once the multisig is enabled, the contract
+/// is paused and unpaused through a `SetPaused` proposal.
+///
+/// It rejects if:
+/// - Sender does not have the `Pauser` role.
+/// - The multisig is enabled.
+/// - It fails to parse the parameter.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "setPaused",
+    parameter = "SetPausedParams",
+    error = "ContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_set_paused<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    // Check that only pausers are authorized to pause/unpause the contract.
+    ensure!(host.state().has_role(&ctx.sender(), Role::Pauser), ContractError::Unauthorized);
+    ensure!(host.state().multisig.is_none(), ContractError::MultisigRequired);
+
+    // Parse the parameter.
+    let params: SetPausedParams = ctx.parameter_cursor().get()?;
+
+    // Update the paused variable.
+    host.state_mut().paused = params.paused;
+
+    logger.log(&VersusEvent::Paused(PausedEvent {
+        paused: params.paused,
+    }))?;
+
+    Ok(())
+}
+
+/// Set the entry fee and approval requirement for players registering
+/// themselves.
+///
+/// It rejects if:
+/// - Sender does not have the `Admin` role.
+/// - It fails to parse the parameter.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "setRegistration",
+    parameter = "RegistrationConfig",
+    error = "ContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_set_registration<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    // Check that only admins are authorized to change the registration.
+    ensure!(host.state().has_role(&ctx.sender(), Role::Admin), ContractError::Unauthorized);
+
+    // Parse the parameter.
+    let config: RegistrationConfig = ctx.parameter_cursor().get()?;
+
+    // Update the registration settings.
+    host.state_mut().registration = config;
+
+    logger.log(&VersusEvent::Registration(RegistrationEvent {
+        config,
+    }))?;
+
+    Ok(())
+}
+
+/// Set the ranking and size of the leaderboard. The leaderboard is rebuilt
+/// from all players, so the cost grows with the number of players.
+///
+/// It rejects if:
+/// - Sender does not have the `Admin` role.
+/// - It fails to parse the parameter.
+/// - The size is zero or larger than `MAX_LEADERBOARD_SIZE`.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "setLeaderboard",
+    parameter = "LeaderboardConfig",
+    error = "ContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_set_leaderboard<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    // Check that only admins are authorized to change the leaderboard.
+    ensure!(host.state().has_role(&ctx.sender(), Role::Admin), ContractError::Unauthorized);
+
+    // Parse the parameter.
+    let config: LeaderboardConfig = ctx.parameter_cursor().get()?;
+    ensure!(
+        config.size > 0 && config.size <= MAX_LEADERBOARD_SIZE,
+        ContractError::InvalidLeaderboardSize
+    );
+
+    // Update the settings and rank every player again.
+    let state = host.state_mut();
+    state.leaderboard_config = config;
+    state.rebuild_leaderboard();
+
+    logger.log(&VersusEvent::Leaderboard(LeaderboardEvent {
+        config,
+    }))?;
+
+    Ok(())
+}
+
+/// Set the streak lengths that log a `StreakMilestone` event when a player
+/// reaches them.
+///
+/// It rejects if:
+/// - Sender does not have the `Admin` role.
+/// - It fails to parse the parameter.
+/// - The milestones are not strictly increasing, contain zero or are more
+///   than `MAX_STREAK_MILESTONES`.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "setStreakMilestones",
+    parameter = "Vec<u64>",
+    error = "ContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_set_streak_milestones<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    // Check that only admins are authorized to change the streak milestones.
+    ensure!(host.state().has_role(&ctx.sender(), Role::Admin), ContractError::Unauthorized);
+
+    // Parse the parameter.
+    let milestones: Vec<u64> = ctx.parameter_cursor().get()?;
+    ensure!(
+        milestones.len() <= MAX_STREAK_MILESTONES
+            && !milestones.contains(&0)
+            && milestones.windows(2).all(|pair| pair[0] < pair[1]),
+        ContractError::InvalidStreakMilestones
+    );
+
+    // Update the streak milestones.
+    host.state_mut().streak_milestones = milestones.clone();
+
+    logger.log(&VersusEvent::StreakMilestones(StreakMilestonesEvent {
+        milestones,
+    }))?;
+
+    Ok(())
+}
+
+/// Set how strictly player updates and battle results are validated.
+///
+/// It rejects if:
+/// - Sender does not have the `Admin` role.
+/// - It fails to parse the parameter.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "setValidationMode",
+    parameter = "ValidationMode",
+    error = "ContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_set_validation_mode<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    // Check that only admins are authorized to change the validation mode.
+    ensure!(host.state().has_role(&ctx.sender(), Role::Admin), ContractError::Unauthorized);
+
+    // Parse the parameter.
+    let mode: ValidationMode = ctx.parameter_cursor().get()?;
+
+    // Update the validation mode.
+    host.state_mut().validation_mode = mode;
+
+    logger.log(&VersusEvent::ValidationMode(ValidationModeEvent {
+        mode,
+    }))?;
+
+    Ok(())
+}
+
+/// Set the fee on the pot of settled challenges, in basis points.
+///
+/// It rejects if:
+/// - Sender does not have the `Admin` role.
+/// - It fails to parse the parameter.
+/// - The fee is larger than 100%.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "setWagerFee",
+    parameter = "u16",
+    error = "ContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_set_wager_fee<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    // Check that only admins are authorized to change the wager fee.
+    ensure!(host.state().has_role(&ctx.sender(), Role::Admin), ContractError::Unauthorized);
+
+    // Parse the parameter.
+    let wager_fee: u16 = ctx.parameter_cursor().get()?;
+    ensure!(wager_fee <= MAX_BASIS_POINTS, ContractError::InvalidFee);
+
+    // Update the wager fee.
+    host.state_mut().wager_fee = wager_fee;
+
+    logger.log(&VersusEvent::WagerFee(WagerFeeEvent {
+        wager_fee,
+    }))?;
+
+    Ok(())
+}
+
+/// Set how long the losing side can dispute a result after it was proposed or
+/// reported. The deadline of results that have already been proposed does not
+/// change.
+///
+/// It rejects if:
+/// - Sender does not have the `Admin` role.
+/// - It fails to parse the parameter.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "setDisputeWindow",
+    parameter = "Duration",
+    error = "ContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_set_dispute_window<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    // Check that only admins are authorized to change the dispute window.
+    ensure!(host.state().has_role(&ctx.sender(), Role::Admin), ContractError::Unauthorized);
+
+    // Parse the parameter.
+    let dispute_window: Duration = ctx.parameter_cursor().get()?;
+
+    // Update the dispute window.
+    host.state_mut().dispute_window = dispute_window;
+
+    logger.log(&VersusEvent::DisputeWindow(DisputeWindowEvent {
+        dispute_window,
+    }))?;
+
+    Ok(())
+}
+
+/// Enable the multisig. Once enabled, upgrades, admin transfers and treasury
+/// withdrawals need the approval of the signers through `submitProposal` and
+/// `approveProposal`, and the multisig can only be changed through a
+/// proposal.
+///
+/// It rejects if:
+/// - Sender is not the admin of the contract instance.
+/// - The multisig is already enabled.
+/// - It fails to parse the parameter.
+/// - The signers are not distinct, too many, or the threshold cannot be
+///   reached.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "setMultisig",
+    parameter = "MultisigConfig",
+    error = "ContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_set_multisig<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    // Check that only the admin is authorized to enable the multisig.
+    ensure_eq!(ctx.sender(), host.state().admin, ContractError::Unauthorized);
+    ensure!(host.state().multisig.is_none(), ContractError::MultisigRequired);
+
+    // Parse the parameter.
+    let config: MultisigConfig = ctx.parameter_cursor().get()?;
+    ensure!(config.is_valid(), ContractError::InvalidMultisig);
+
+    host.state_mut().multisig = Some(config.clone());
+
+    logger.log(&VersusEvent::Multisig(MultisigEvent {
+        config: Some(config),
+    }))?;
+
+    Ok(())
+}
+
+/// Withdraw CCD collected from entry fees to an account.
+///
+/// It rejects if:
+/// - Sender is not the admin of the contract instance.
+/// - It fails to parse the parameter.
+/// - The treasury holds less than the requested amount.
+/// - The transfer fails.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "withdrawTreasury",
+    parameter = "WithdrawTreasuryParams",
+    error = "ContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_withdraw_treasury<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    // Check that only the admin is authorized to withdraw from the treasury.
+    ensure_eq!(ctx.sender(), host.state().admin, ContractError::Unauthorized);
+    ensure!(host.state().multisig.is_none(), ContractError::MultisigRequired);
+
+    // Parse the parameter.
+    let params: WithdrawTreasuryParams = ctx.parameter_cursor().get()?;
+    ensure!(params.amount <= host.state().treasury, ContractError::InsufficientFunds);
+
+    // Update the treasury before transferring.
+    host.state_mut().treasury -= params.amount;
+    host.invoke_transfer(&params.to, params.amount)?;
+
+    logger.log(&VersusEvent::TreasuryWithdrawal(TreasuryWithdrawalEvent {
+        to: params.to,
+        amount: params.amount,
+    }))?;
+
+    Ok(())
+}
+
+/// Grant a role to an address.
+///
+/// It rejects if:
+/// - Sender does not have the `Admin` role.
+/// - The multisig is enabled.
+/// - It fails to parse the parameter.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "grantRole",
+    parameter = "RoleParams",
+    error = "ContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_grant_role<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    // Check that only admins are authorized to grant roles.
+    ensure!(host.state().has_role(&ctx.sender(), Role::Admin), ContractError::Unauthorized);
+    ensure!(host.state().multisig.is_none(), ContractError::MultisigRequired);
+
+    // Parse the parameter.
+    let params: RoleParams = ctx.parameter_cursor().get()?;
+
+    // Grant the role.
+    host.state_mut().roles.insert((params.address, params.role));
+
+    logger.log(&VersusEvent::GrantRole(GrantRoleEvent {
+        address: params.address,
+        role: params.role,
+    }))?;
+
+    Ok(())
+}
+
+/// Revoke a role from an address. The `Admin` role of the admin address
+/// cannot be revoked; use `proposeAdmin` to hand it over instead.
+///
+/// It rejects if:
+/// - Sender does not have the `Admin` role.
+/// - It fails to parse the parameter.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "revokeRole",
+    parameter = "RoleParams",
+    error = "ContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_revoke_role<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    // Check that only admins are authorized to revoke roles.
+    ensure!(host.state().has_role(&ctx.sender(), Role::Admin), ContractError::Unauthorized);
+
+    // Parse the parameter.
+    let params: RoleParams = ctx.parameter_cursor().get()?;
+
+    // Revoke the role.
+    host.state_mut().roles.remove(&(params.address, params.role));
+
+    logger.log(&VersusEvent::RevokeRole(RevokeRoleEvent {
+        address: params.address,
+        role: params.role,
+    }))?;
+
+    Ok(())
+}
+
+/// Set the rating engine used for all future battle results. Existing
+/// ratings are kept and carried over to the new engine.
+///
+/// It rejects if:
+/// - Sender does not have the `Admin` role.
+/// - It fails to parse the parameter.
+/// - The engine parameters are invalid, e.g. a k-factor of zero.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "setRatingEngine",
+    parameter = "RatingEngineConfig",
+    error = "ContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_set_rating_engine<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    // Check that only admins are authorized to change the rating engine.
+    ensure!(host.state().has_role(&ctx.sender(), Role::Admin), ContractError::Unauthorized);
+
+    // Parse the parameter.
+    let engine: RatingEngineConfig = ctx.parameter_cursor().get()?;
+    ensure!(engine.is_valid(), ContractError::InvalidRatingEngine);
+
+    // Update the rating engine.
+    host.state_mut().rating_engine = engine;
+
+    logger.log(&VersusEvent::RatingEngine(RatingEngineEvent {
+        engine,
+    }))?;
+
+    Ok(())
+}
+
+/// Start a new season. Results reported while the season is in progress are
+/// recorded both in the lifetime totals and in the season stats.
+///
+/// It rejects if:
+/// - Sender does not have the `Admin` role.
+/// - A season is already in progress.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "startSeason",
+    error = "ContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_start_season<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    // Check that only admins are authorized to start a season.
+    ensure!(host.state().has_role(&ctx.sender(), Role::Admin), ContractError::Unauthorized);
+    ensure!(host.state().active_season().is_none(), ContractError::SeasonInProgress);
+
+    let season = host.state_mut().start_season(ctx.metadata().slot_time());
+
+    logger.log(&VersusEvent::SeasonStarted(SeasonStartedEvent {
+        season,
+    }))?;
+
+    Ok(())
+}
+
+/// End the season in progress, pay out its prizes and optionally start the
+/// next one. The stats of the ended season stay readable through
+/// `getSeasonPlayerData`.
+///
+/// It rejects if:
+/// - Sender does not have the `Admin` role.
+/// - It fails to parse the parameter.
+/// - The multisig is enabled and prizes are to be paid out.
+/// - No season is in progress.
+/// - A prize pool holds less than its payout.
+/// - A prize transfer fails.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "endSeason",
+    parameter = "EndSeasonParams",
+    error = "ContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_end_season<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    // Check that only admins are authorized to end a season.
+    ensure!(host.state().has_role(&ctx.sender(), Role::Admin), ContractError::Unauthorized);
+
+    // Parse the parameter.
+    let params: EndSeasonParams = ctx.parameter_cursor().get()?;
+    // Prizes are paid out through a multisig proposal instead.
+    ensure!(
+        params.payouts.is_empty() || host.state().multisig.is_none(),
+        ContractError::MultisigRequired
+    );
+
+    let now = ctx.metadata().slot_time();
+    let state = host.state_mut();
+    let season = state.active_season().ok_or(ContractError::NoActiveSeason)?;
+    let players = {
+        let mut info = state.seasons.get_mut(&season).ok_or(ContractError::SeasonDoesNotExist)?;
+        info.ended_at = Some(now);
+        info.players
+    };
+
+    logger.log(&VersusEvent::SeasonEnded(SeasonEndedEvent {
+        season,
+        players,
+    }))?;
+
+    if params.start_next {
+        let season = state.start_season(now);
+        logger.log(&VersusEvent::SeasonStarted(SeasonStartedEvent {
+            season,
+        }))?;
+    }
+
+    for payout in params.payouts {
+        pay_prize(ctx.self_address(), host, payout, logger)?;
+    }
+
+    Ok(())
+}
+
+/// Deposit CIS-2 tokens into a prize pool. This is the hook called by a
+/// token contract when tokens are transferred to this contract. The
+/// additional data of the transfer must hold the name of the prize pool.
+///
+/// It rejects if:
+/// - The contract is paused.
+/// - Sender is not a token contract on the prize token allowlist.
+/// - It fails to parse the parameter or the name of the prize pool.
+/// - The deposit would overflow the balance of the prize pool.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "onReceivingCIS2",
+    parameter = "OnReceivingCis2Params<ContractTokenId, ContractTokenAmount>",
+    error = "ContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_on_receiving_cis2<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    // Check that contract is not paused.
+    ensure!(!host.state().paused, ContractError::ContractPaused);
+    // Check that the tokens come from an allowed token contract.
+    let token_contract = match ctx.sender() {
+        Address::Contract(token_contract) => token_contract,
+        Address::Account(_) => bail!(ContractError::TokenNotAllowed),
+    };
+    ensure!(host.state().prize_tokens.contains(&token_contract), ContractError::TokenNotAllowed);
+
+    // Parse the parameter.
+    let params: OnReceivingCis2Params<ContractTokenId, ContractTokenAmount> =
+        ctx.parameter_cursor().get()?;
+    let pool: String = from_bytes(params.data.as_ref())?;
+
+    let key = PrizePoolKey {
+        pool,
+        token_contract,
+        token_id: params.token_id,
+    };
+    let state = host.state_mut();
+    let balance = state.prize_pools.get(&key).map(|balance| *balance).unwrap_or_default();
+    let new_balance =
+        balance.0.checked_add(params.amount.0).ok_or(ContractError::PrizePoolOverflow)?;
+    state.prize_pools.insert(key.clone(), TokenAmountU64(new_balance));
+
+    logger.log(&VersusEvent::PrizeDeposited(PrizeDepositedEvent {
+        key,
+        amount: params.amount,
+        from: params.from,
+    }))?;
+
+    Ok(())
+}
+
+/// Pay out a prize from a prize pool.
+///
+/// It rejects if:
+/// - Sender does not have the `Admin` role.
+/// - The multisig is enabled.
+/// - It fails to parse the parameter.
+/// - The prize pool holds less than the payout.
+/// - The transfer fails.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "payoutPrize",
+    parameter = "PrizePayout",
+    error = "ContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_payout_prize<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    // Check that only admins are authorized to pay out prizes.
+    ensure!(host.state().has_role(&ctx.sender(), Role::Admin), ContractError::Unauthorized);
+    ensure!(host.state().multisig.is_none(), ContractError::MultisigRequired);
+
+    // Parse the parameter.
+    let payout: PrizePayout = ctx.parameter_cursor().get()?;
+
+    pay_prize(ctx.self_address(), host, payout, logger)
+}
+
+/// Add a CIS-2 token contract to or remove it from the prize token
+/// allowlist.
+///
+/// It rejects if:
+/// - Sender does not have the `Admin` role.
+/// - It fails to parse the parameter.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "setPrizeToken",
+    parameter = "SetPrizeTokenParams",
+    error = "ContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_set_prize_token<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    // Check that only admins are authorized to change the allowlist.
+    ensure!(host.state().has_role(&ctx.sender(), Role::Admin), ContractError::Unauthorized);
+
+    // Parse the parameter.
+    let params: SetPrizeTokenParams = ctx.parameter_cursor().get()?;
+
+    if params.allowed {
+        host.state_mut().prize_tokens.insert(params.token_contract);
+    } else {
+        host.state_mut().prize_tokens.remove(&params.token_contract);
+    }
+
+    logger.log(&VersusEvent::PrizeToken(PrizeTokenEvent {
+        token_contract: params.token_contract,
+        allowed: params.allowed,
+    }))?;
+
+    Ok(())
+}
+
+/// Set the addresses of contracts implementing a standard on behalf of this
+/// contract, as reported by `supports`. An empty list removes the
+/// implementors of the standard.
+///
+/// It rejects if:
+/// - Sender does not have the `Admin` role.
+/// - It fails to parse the parameter.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "setImplementors",
+    parameter = "SetImplementorsParams",
+    error = "ContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_set_implementors<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    // Check that only admins are authorized to set implementors.
+    ensure!(host.state().has_role(&ctx.sender(), Role::Admin), ContractError::Unauthorized);
+
+    // Parse the parameter.
+    let params: SetImplementorsParams = ctx.parameter_cursor().get()?;
+
+    host.state_mut().set_implementors(params.id.clone(), params.implementors.clone());
+
+    logger.log(&VersusEvent::Implementors(ImplementorsEvent {
+        id: params.id,
+        implementors: params.implementors,
+    }))?;
+
+    Ok(())
+}
+
+/// Enable or disable a game server that signs battle results for
+/// `submitSignedResult`.
+///
+/// It rejects if:
+/// - Sender does not have the `Admin` role.
+/// - The multisig is enabled.
+/// - It fails to parse the parameter.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "setGameServer",
+    parameter = "SetGameServerParams",
+    error = "ContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_set_game_server<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    // Check that only admins are authorized to change the game servers.
+    ensure!(host.state().has_role(&ctx.sender(), Role::Admin), ContractError::Unauthorized);
+    ensure!(host.state().multisig.is_none(), ContractError::MultisigRequired);
+
+    // Parse the parameter.
+    let params: SetGameServerParams = ctx.parameter_cursor().get()?;
+
+    host.state_mut().set_game_server(params.public_key, params.enabled);
+
+    logger.log(&VersusEvent::GameServer(GameServerEvent {
+        public_key: params.public_key,
+        enabled: params.enabled,
+    }))?;
+
+    Ok(())
+}
+
+/// Schedule an upgrade of this smart contract instance to a new module with
+/// an optional migration function. The upgrade can be executed with
+/// `executeUpgrade` once its execution time has passed. A new schedule
+/// replaces the scheduled upgrade.
+///
+/// It rejects if:
+/// - Sender is not the admin of the contract instance.
+/// - The multisig is enabled.
+/// - It fails to parse the parameter.
+/// - The execution time is earlier than `UPGRADE_DELAY` from now.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "scheduleUpgrade",
+    parameter = "UpgradeParams",
+    error = "ContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_schedule_upgrade<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    // Check that only the admin is authorized to schedule upgrades.
+    ensure_eq!(ctx.sender(), host.state().admin, ContractError::Unauthorized);
+    ensure!(host.state().multisig.is_none(), ContractError::MultisigRequired);
+
+    // Parse the parameter.
+    let params: UpgradeParams = ctx.parameter_cursor().get()?;
+    let module = params.module;
+    let execute_after = params.execute_after;
+
+    host.state_mut().schedule_upgrade(params, ctx.metadata().slot_time())?;
+
+    logger.log(&VersusEvent::UpgradeScheduled(UpgradeScheduledEvent {
+        module,
+        execute_after,
+    }))?;
+
+    Ok(())
+}
+
+/// Cancel the scheduled upgrade.
+///
+/// It rejects if:
+/// - Sender is not the admin of the contract instance.
+/// - The multisig is enabled.
+/// - No upgrade has been scheduled.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "cancelUpgrade",
+    error = "ContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_cancel_upgrade<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    // Check that only the admin is authorized to cancel upgrades.
+    ensure_eq!(ctx.sender(), host.state().admin, ContractError::Unauthorized);
+    ensure!(host.state().multisig.is_none(), ContractError::MultisigRequired);
+
+    let pending_upgrade =
+        host.state_mut().pending_upgrade.take().ok_or(ContractError::NoPendingUpgrade)?;
+
+    logger.log(&VersusEvent::UpgradeCancelled(UpgradeCancelledEvent {
+        module: pending_upgrade.module,
+    }))?;
+
+    Ok(())
+}
+
+/// Execute the scheduled upgrade of this smart contract instance and call
+/// optionally a migration function after the upgrade. Anyone can execute the
+/// upgrade once its execution time has passed, since it was authorized when
+/// it was scheduled.
+///
+/// It rejects if:
+/// - No upgrade has been scheduled.
+/// - The execution time of the upgrade has not passed.
+/// - If the ugrade fails.
+/// - If the migration invoke fails.
+///
+/// This function is marked as `low_level`. This is **necessary** since the
+/// high-level mutable functions store the state of the contract at the end of
+/// execution. This conflicts with migration since the shape of the state
+/// **might** be changed by the migration function. If the state is then written
+/// by this function it would overwrite the state stored by the migration
+/// function.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "executeUpgrade",
+    error = "CustomContractError",
+    low_level,
+    enable_logger
+)]
+fn contract_execute_upgrade<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    // Read the top-level contract state.
+    let mut state: State<S> = host.state().read_root()?;
+
+    let params = state.pending_upgrade.take().ok_or(ContractError::NoPendingUpgrade)?;
+    ensure!(ctx.metadata().slot_time() >= params.execute_after, ContractError::UpgradeNotReady);
+    // Clear the scheduled upgrade before the migration reads the state.
+    host.state_mut().write_root(&state);
+
+    upgrade(ctx, host, params, logger)
+}
+
+/// Migrate the state written by an earlier module to the layout of this
+/// module, one version at a time. It is meant to be called as the migration
+/// function of an upgrade, see `scheduleUpgrade`.
+///
+/// It rejects if:
+/// - Sender is neither this contract instance nor the admin.
+/// - The state already has the layout of this module.
+/// - The state has a layout this module cannot migrate.
+/// - It fails to parse the state.
+///
+/// This function is marked as `low_level`, since the state cannot be read as
+/// `State` until it has been migrated.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "migrate",
+    error = "CustomContractError",
+    low_level,
+    enable_logger
+)]
+fn contract_migrate<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<S, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    let (from, admin) = read_state_header(host.state())?;
+    // Check that only this contract instance, as part of an upgrade, and the
+    // admin are authorized to migrate.
+    let sender = ctx.sender();
+    ensure!(
+        sender == Address::Contract(ctx.self_address()) || sender == admin,
+        ContractError::Unauthorized
+    );
+
+    let now = ctx.metadata().slot_time();
+    let (state_api, state_builder) = host.state_and_builder();
+    migrate_state(state_api, state_builder, from, now)?;
+
+    logger.log(&VersusEvent::StateMigrated(StateMigratedEvent {
+        from,
+        to: STATE_VERSION,
+    }))?;
+
+    Ok(())
+}
+
+/// Submit a proposal for a sensitive admin action. The submission counts as
+/// the approval of the proposer, so the proposal runs right away if the
+/// threshold is 1. Returns the id of the new proposal.
+///
+/// It rejects if:
+/// - The multisig is not enabled.
+/// - Sender is not a signer.
+/// - It fails to parse the parameter.
+/// - The expiry is not in the future.
+/// - The proposal runs and its action fails.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "submitProposal",
+    parameter = "SubmitProposalParams",
+    return_value = "ProposalId",
+    error = "CustomContractError",
+    mutable,
+    enable_logger
+)]
+fn contract_submit_proposal<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<ProposalId> {
+    // Check that only signers are authorized to submit proposals.
+    let signer = host.state().ensure_signer(&ctx.sender())?;
+    // Parse the parameter.
+    let params: SubmitProposalParams = ctx.parameter_cursor().get()?;
+    ensure!(params.expiry > ctx.metadata().slot_time(), ContractError::InvalidExpiry);
+
+    let proposal_id = host.state_mut().submit_proposal(signer, params.action, params.expiry);
+    logger.log(&VersusEvent::ProposalSubmitted(ProposalSubmittedEvent {
+        proposal: proposal_id,
+        proposer: signer,
+        expiry: params.expiry,
+    }))?;
+
+    approve_and_execute(ctx, host, proposal_id, signer, logger)?;
+    Ok(proposal_id)
+}
+
+/// Approve a proposal. The proposal runs once it has the approvals of as
+/// many current signers as the threshold.
+///
+/// It rejects if:
+/// - The multisig is not enabled.
+/// - Sender is not a signer.
+/// - It fails to parse the parameter.
+/// - The proposal does not exist, has expired or has already run.
+/// - Sender has already approved the proposal.
+/// - The proposal runs and its action fails.
+#[receive(
+    contract = "Versus-League-Manager",
+    name = "approveProposal",
+    parameter = "ProposalId",
+    error = "CustomContractError",
+    mutable,
+    enable_logger
+)]
+fn contract_approve_proposal<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    // Check that only signers are authorized to approve proposals.
+    let signer = host.state().ensure_signer(&ctx.sender())?;
+    // Parse the parameter.
+    let proposal_id: ProposalId = ctx.parameter_cursor().get()?;
+
+    approve_and_execute(ctx, host, proposal_id, signer, logger)
+}
+
+#[concordium_cfg_test]
+mod tests {
+    use super::*;
+    use test_infrastructure::*;
+
+    const ADMIN_ACCOUNT: AccountAddress = AccountAddress([2u8; 32]);
+    const ADMIN_ADDRESS: Address = Address::Account(ADMIN_ACCOUNT);
+    const NEW_ADMIN_ACCOUNT: AccountAddress = AccountAddress([3u8; 32]);
+    const NEW_ADMIN_ADDRESS: Address = Address::Account(NEW_ADMIN_ACCOUNT);
+    const PLAYER_ACCOUNT: AccountAddress = AccountAddress([4u8; 32]);
+    const PLAYER_ADDRESS: Address = Address::Account(PLAYER_ACCOUNT);
+    const OPPONENT_ACCOUNT: AccountAddress = AccountAddress([5u8; 32]);
+    const OPPONENT_ADDRESS: Address = Address::Account(OPPONENT_ACCOUNT);
+    const REPORTER_ACCOUNT: AccountAddress = AccountAddress([6u8; 32]);
+    const REPORTER_ADDRESS: Address = Address::Account(REPORTER_ACCOUNT);
+    const TOKEN_CONTRACT: ContractAddress = ContractAddress {
+        index: 10,
+        subindex: 0,
+    };
+    /// The public key of a game server, from the secret key `[7u8; 32]`.
+    const GAME_SERVER_KEY: PublicKeyEd25519 = PublicKeyEd25519([
+        234, 74, 108, 99, 226, 156, 82, 10, 190, 245, 80, 123, 19, 46, 197, 249, 149, 71, 118, 174,
+        190, 190, 123, 146, 66, 30, 234, 105, 20, 70, 210, 44,
+    ]);
+    /// The signature of `GAME_SERVER_KEY` on the message of
+    /// `signed_result_message`.
+    const GAME_SERVER_SIGNATURE: SignatureEd25519 = SignatureEd25519([
+        57, 125, 252, 73, 58, 23, 187, 127, 147, 229, 180, 103, 31, 118, 202, 78, 130, 186, 8, 10,
+        46, 131, 65, 18, 187, 188, 116, 244, 11, 1, 242, 84, 76, 49, 72, 116, 131, 240, 163, 24,
+        56, 120, 126, 109, 141, 255, 94, 110, 248, 74, 132, 162, 107, 153, 246, 23, 254, 244, 63,
+        217, 138, 132, 215, 13,
+    ]);
+
+    /// Test helper function which creates a contract state with the
+    /// `ADMIN_ADDRESS` as admin and no players.
+    fn initial_state<S: HasStateApi>(state_builder: &mut StateBuilder<S>) -> State<S> {
+        State::new(state_builder, ADMIN_ADDRESS)
+    }
+
+    /// Test helper function which creates the message signed by
+    /// `GAME_SERVER_KEY`: battle 1 won by `PLAYER_ADDRESS` against
+    /// `OPPONENT_ADDRESS`, reported to contract `<1, 0>` with nonce 0.
+    fn signed_result_message() -> SignedResultMessage {
+        SignedResultMessage {
+            contract_address: ContractAddress {
+                index: 1,
+                subindex: 0,
+            },
+            nonce: 0,
+            expiry: Timestamp::from_timestamp_millis(10_000),
+            report: ReportMatchParams {
+                battle_id: 1,
+                winner: PLAYER_ADDRESS,
+                loser: OPPONENT_ADDRESS,
+                draw: false,
+                score: None,
+                challenge: None,
+            },
+        }
+    }
+
+    /// Test helper function which creates the parameter for a battle won by
+    /// `PLAYER_ADDRESS` against `OPPONENT_ADDRESS`.
+    fn match_report(battle_id: BattleId) -> ReportMatchParams {
+        ReportMatchParams {
+            battle_id,
+            winner: PLAYER_ADDRESS,
+            loser: OPPONENT_ADDRESS,
+            draw: false,
+            score: None,
+            challenge: None,
+        }
+    }
+
+    /// Test helper function which adds an active player with the default
+    /// rating to the state.
+    fn add_player<S: HasStateApi>(state: &mut State<S>, player: Address) {
+        let registered_at = Timestamp::from_timestamp_millis(0);
+        let player_data = PlayerData::new(PlayerState::Active, DEFAULT_RATING, registered_at);
+        state.player_data.insert(player, player_data);
+        state.player_count += 1;
+    }
+
+    /// Test admin can transfer the admin role to a new admin address that
+    /// accepts it.
+    #[concordium_test]
+    fn test_update_admin() {
+        // Set up the context
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+        ctx.set_sender(ADMIN_ADDRESS);
+        let mut logger = TestLogger::init();
+
+        // Set up the parameter.
+        let parameter_bytes = to_bytes(&ProposeAdminParams {
+            new_admin: NEW_ADMIN_ADDRESS,
+            expiry: None,
+        });
+        ctx.set_parameter(&parameter_bytes);
+
+        // Set up the state and host.
+        let mut state_builder = TestStateBuilder::new();
+        let state = initial_state(&mut state_builder);
+        let mut host = TestHost::new(state, state_builder);
+
+        // Check the admin state.
+        claim_eq!(host.state().admin, ADMIN_ADDRESS, "Admin should be the old admin address");
+
+        // Call the contract function.
+        let result: ContractResult<()> = contract_propose_admin(&ctx, &mut host, &mut logger);
+
+        // Check the result.
+        claim!(result.is_ok(), "Results in rejection");
+
+        // Check that the admin only changes once the new admin accepts.
+        claim_eq!(host.state().admin, ADMIN_ADDRESS, "Admin should be the old admin address");
+        ctx.set_sender(NEW_ADMIN_ADDRESS);
+        let result: ContractResult<()> = contract_accept_admin(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Results in rejection");
+
+        // Check the admin state.
+        claim_eq!(host.state().admin, NEW_ADMIN_ADDRESS, "Admin should be the new admin address");
+        claim_eq!(host.state().pending_admin, None, "Pending admin should be cleared");
+
+        // Check that the old admin no longer holds the operational roles.
+        for role in OPERATIONAL_ROLES {
+            claim!(!host.state().has_role(&ADMIN_ADDRESS, role), "Role should be revoked");
+        }
+
+        // Check the logs
+        claim_eq!(logger.logs.len(), 6, "Exactly six events should be logged");
+
+        // Check the events
+        claim!(
+            logger.logs.contains(&to_bytes(&VersusEvent::NewAdmin(NewAdminEvent {
+                new_admin: NEW_ADMIN_ADDRESS,
+            }))),
+            "Missing event for the new admin"
+        );
+        claim!(
+            logger.logs.contains(&to_bytes(&VersusEvent::RevokeRole(RevokeRoleEvent {
+                address: ADMIN_ADDRESS,
+                role: Role::Reporter,
+            }))),
+            "Missing event for the revoked role"
+        );
+    }
+
+    /// Test that only the current admin can update the admin address.
+    #[concordium_test]
+    fn test_update_admin_not_authorized() {
+        // Set up the context.
+        let mut ctx = TestReceiveContext::empty();
+        // NEW_ADMIN is not the current admin but tries to update the admin variable to
+        // its own address.
+        ctx.set_sender(NEW_ADMIN_ADDRESS);
+        let mut logger = TestLogger::init();
+
+        // Set up the parameter.
+        let parameter_bytes = to_bytes(&ProposeAdminParams {
+            new_admin: NEW_ADMIN_ADDRESS,
+            expiry: None,
+        });
+        ctx.set_parameter(&parameter_bytes);
+
+        // Set up the state and host.
+        let mut state_builder = TestStateBuilder::new();
+        let state = initial_state(&mut state_builder);
+        let mut host = TestHost::new(state, state_builder);
+
+        // Check the admin state.
+        claim_eq!(host.state().admin, ADMIN_ADDRESS, "Admin should be the old admin address");
+
+        // Call the contract function.
+        let result: ContractResult<()> = contract_propose_admin(&ctx, &mut host, &mut logger);
+
+        // Check that invoke failed.
+        claim_eq!(
+            result,
+            Err(ContractError::Unauthorized),
+            "Update admin should fail because not the current admin tries to update"
+        );
+
+        // Check the admin state.
+        claim_eq!(host.state().admin, ADMIN_ADDRESS, "Admin should be still the old admin address");
+    }
+
+    /// Test pausing the contract.
+    #[concordium_test]
+    fn test_pause() {
+        // Set up the context.
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(ADMIN_ADDRESS);
+        let mut logger = TestLogger::init();
+
+        // Set up the parameter to pause the contract.
+        let parameter_bytes = to_bytes(&true);
+        ctx.set_parameter(&parameter_bytes);
+
+        // Set up the state and host.
+        let mut state_builder = TestStateBuilder::new();
+        let state = initial_state(&mut state_builder);
+        let mut host = TestHost::new(state, state_builder);
+
+        // Call the contract function.
+        let result: ContractResult<()> = contract_set_paused(&ctx, &mut host, &mut logger);
+
+        // Check the result.
+        claim!(result.is_ok(), "Results in rejection");
+
+        // Check contract is paused.
+        claim_eq!(host.state().paused, true, "Smart contract should be paused");
+
+        // Check the event.
+        claim!(
+            logger.logs.contains(&to_bytes(&VersusEvent::Paused(PausedEvent {
+                paused: true,
+            }))),
+            "Missing event for pausing the contract"
+        );
+    }
+
+    /// Test unpausing the contract.
+    #[concordium_test]
+    fn test_unpause() {
+        // Set up the context.
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(ADMIN_ADDRESS);
+        let mut logger = TestLogger::init();
+
+        // Set up the parameter to pause the contract.
+        let parameter_bytes = to_bytes(&true);
+        ctx.set_parameter(&parameter_bytes);
+
+        // Set up the state and host.
+        let mut state_builder = TestStateBuilder::new();
+        let state = initial_state(&mut state_builder);
+        let mut host = TestHost::new(state, state_builder);
+
+        // Call the contract function.
+        let result: ContractResult<()> = contract_set_paused(&ctx, &mut host, &mut logger);
+
+        // Check the result.
+        claim!(result.is_ok(), "Results in rejection");
+
+        // Check contract is paused.
+        claim_eq!(host.state().paused, true, "Smart contract should be paused");
+
+        // Set up the parameter to unpause the contract.
+        let parameter_bytes = to_bytes(&false);
+        ctx.set_parameter(&parameter_bytes);
+
+        // Call the contract function.
+        let result: ContractResult<()> = contract_set_paused(&ctx, &mut host, &mut logger);
+
+        // Check the result.
+        claim!(result.is_ok(), "Results in rejection");
+
+        // Check contract is unpaused.
+        claim_eq!(host.state().paused, false, "Smart contract should be unpaused");
+    }
+
+    /// Test that only the current admin can pause/unpause the contract.
+    #[concordium_test]
+    fn test_pause_not_authorized() {
+        // Set up the context.
+        let mut ctx = TestReceiveContext::empty();
+        // NEW_ADMIN is not the current admin but tries to pause/unpause the contract.
+        ctx.set_sender(NEW_ADMIN_ADDRESS);
+        let mut logger = TestLogger::init();
+
+        // Set up the parameter to pause the contract.
+        let parameter_bytes = to_bytes(&true);
+        ctx.set_parameter(&parameter_bytes);
+
+        // Set up the state and host.
+        let mut state_builder = TestStateBuilder::new();
+        let state = initial_state(&mut state_builder);
+        let mut host = TestHost::new(state, state_builder);
+
+        // Call the contract function.
+        let result: ContractResult<()> = contract_set_paused(&ctx, &mut host, &mut logger);
+
+        // Check that invoke failed.
+        claim_eq!(
+            result,
+            Err(ContractError::Unauthorized),
+            "Pause should fail because not the current admin tries to invoke it"
+        );
+    }
+
+    /// Test the Elo engine on a battle between two equally rated players.
+    #[concordium_test]
+    fn test_elo_equal_ratings() {
+        let elo = Elo {
+            k_factor: 32,
+        };
+
+        let winner = elo.rate(&DEFAULT_RATING, &DEFAULT_RATING, BattleResult::Win);
+        let loser = elo.rate(&DEFAULT_RATING, &DEFAULT_RATING, BattleResult::Loss);
+
+        claim_eq!(winner.rating, 1_516_000_000, "Winner should gain half the k-factor");
+        claim_eq!(loser.rating, 1_484_000_000, "Loser should lose half the k-factor");
+        claim_eq!(winner.deviation, DEFAULT_RATING.deviation, "Elo should not touch the deviation");
+    }
+
+    /// Test the Elo engine gives a favourite less for a win than an underdog.
+    #[concordium_test]
+    fn test_elo_uneven_ratings() {
+        let elo = Elo {
+            k_factor: 32,
+        };
+        let strong = Rating {
+            rating: 1_700_000_000,
+            ..DEFAULT_RATING
+        };
+
+        let favourite = elo.rate(&strong, &DEFAULT_RATING, BattleResult::Win);
+        let underdog = elo.rate(&DEFAULT_RATING, &strong, BattleResult::Win);
+
+        // The expected score of the favourite is about 0.76.
+        claim!(
+            favourite.rating > 1_707_000_000 && favourite.rating < 1_708_000_000,
+            "Favourite should gain about 7.7 points"
+        );
+        claim!(
+            underdog.rating > 1_524_000_000 && underdog.rating < 1_525_000_000,
+            "Underdog should gain about 24.3 points"
+        );
+    }
+
+    /// Test the Glicko-2 engine on a battle between two new players.
+    #[concordium_test]
+    fn test_glicko2_new_players() {
+        let glicko2 = Glicko2 {
+            tau: 500_000,
+        };
+
+        let winner = glicko2.rate(&DEFAULT_RATING, &DEFAULT_RATING, BattleResult::Win);
+        let loser = glicko2.rate(&DEFAULT_RATING, &DEFAULT_RATING, BattleResult::Loss);
+
+        // The reference implementation gives 1662.3 and 290.3.
+        claim!(
+            winner.rating > 1_661_000_000 && winner.rating < 1_664_000_000,
+            "Winner should gain about 162 points"
+        );
+        claim!(
+            winner.deviation > 289_000_000 && winner.deviation < 292_000_000,
+            "Deviation should shrink to about 290"
+        );
+        claim!(
+            winner.volatility > 59_000 && winner.volatility < 61_000,
+            "Volatility should stay about 0.06"
+        );
+        claim_eq!(
+            winner.rating - DEFAULT_RATING.rating,
+            DEFAULT_RATING.rating - loser.rating,
+            "Rating changes should be symmetric"
+        );
+    }
+
+    /// Test that a battle result updates the rating of the player.
+    #[concordium_test]
+    fn test_update_battle_result_rating() {
+        // Set up the context.
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+        ctx.set_sender(ADMIN_ADDRESS);
+        let mut logger = TestLogger::init();
+
+        // Set up the parameter.
+        let parameter_bytes = to_bytes(&UpdateBattleResultParams {
+            player: PLAYER_ADDRESS,
+            result: BattleResult::Win,
+            score: None,
+            opponent: Some(OPPONENT_ADDRESS),
+        });
+        ctx.set_parameter(&parameter_bytes);
+
+        // Set up the state and host.
+        let mut state_builder = TestStateBuilder::new();
+        let mut state = initial_state(&mut state_builder);
+        add_player(&mut state, PLAYER_ADDRESS);
+        add_player(&mut state, OPPONENT_ADDRESS);
+        let mut host = TestHost::new(state, state_builder);
+
+        // Call the contract function.
+        let result: ContractResult<()> = update_battle_result(&ctx, &mut host, &mut logger);
+
+        // Check the result.
+        claim!(result.is_ok(), "Results in rejection");
+
+        // Check the player data.
+        let player_data = host.state().player_data.get(&PLAYER_ADDRESS).unwrap();
+        claim_eq!(player_data.wins, 1, "Player should have one win");
+        claim_eq!(player_data.rating.rating, 1_516_000_000, "Player should gain 16 points");
+        let opponent_data = host.state().player_data.get(&OPPONENT_ADDRESS).unwrap();
+        claim_eq!(opponent_data.rating, DEFAULT_RATING, "Opponent should be unchanged");
+    }
+
+    /// Test that only the admin can change the rating engine.
+    #[concordium_test]
+    fn test_set_rating_engine() {
+        // Set up the context.
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(NEW_ADMIN_ADDRESS);
+        let mut logger = TestLogger::init();
+
+        // Set up the parameter.
+        let engine = RatingEngineConfig::Glicko2(Glicko2 {
+            tau: 500_000,
+        });
+        let parameter_bytes = to_bytes(&engine);
+        ctx.set_parameter(&parameter_bytes);
+
+        // Set up the state and host.
+        let mut state_builder = TestStateBuilder::new();
+        let state = initial_state(&mut state_builder);
+        let mut host = TestHost::new(state, state_builder);
+
+        // Call the contract function as a non-admin.
+        let result: ContractResult<()> = contract_set_rating_engine(&ctx, &mut host, &mut logger);
+        claim_eq!(result, Err(ContractError::Unauthorized), "Only the admin can set the engine");
+
+        // Call the contract function as the admin.
+        ctx.set_sender(ADMIN_ADDRESS);
+        let result: ContractResult<()> = contract_set_rating_engine(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Results in rejection");
+        claim_eq!(host.state().rating_engine, engine, "Rating engine should be updated");
+    }
+
+    /// Test that a match updates both players in one call.
+    #[concordium_test]
+    fn test_report_match() {
+        // Set up the context.
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(ADMIN_ADDRESS);
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(1));
+        let mut logger = TestLogger::init();
+
+        // Set up the parameter.
+        let parameter_bytes = to_bytes(&ReportMatchParams {
+            battle_id: 1,
+            winner: PLAYER_ADDRESS,
+            loser: OPPONENT_ADDRESS,
+            draw: false,
+            score: None,
+            challenge: None,
+        });
+        ctx.set_parameter(&parameter_bytes);
+
+        // Set up the state and host.
+        let mut state_builder = TestStateBuilder::new();
+        let mut state = initial_state(&mut state_builder);
+        add_player(&mut state, PLAYER_ADDRESS);
+        add_player(&mut state, OPPONENT_ADDRESS);
+        let mut host = TestHost::new(state, state_builder);
+
+        // Call the contract function.
+        let result: ContractResult<()> = contract_report_match(&ctx, &mut host, &mut logger);
+
+        // Check the result.
+        claim!(result.is_ok(), "Results in rejection");
+
+        // Check both players.
+        let winner_data = host.state().player_data.get(&PLAYER_ADDRESS).unwrap();
+        claim_eq!(winner_data.wins, 1, "Winner should have one win");
+        claim_eq!(winner_data.rating.rating, 1_516_000_000, "Winner should gain 16 points");
+        let loser_data = host.state().player_data.get(&OPPONENT_ADDRESS).unwrap();
+        claim_eq!(loser_data.losses, 1, "Loser should have one loss");
+        claim_eq!(loser_data.rating.rating, 1_484_000_000, "Loser should lose 16 points");
+
+        // Check the logs.
+        claim_eq!(logger.logs.len(), 1, "Exactly one event should be logged");
+    }
+
+    /// Test that reporting the same battle twice is rejected.
+    #[concordium_test]
+    fn test_report_match_duplicate() {
+        // Set up the context.
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(ADMIN_ADDRESS);
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(1));
+        let mut logger = TestLogger::init();
+
+        // Set up the parameter.
+        let parameter_bytes = to_bytes(&ReportMatchParams {
+            battle_id: 1,
+            winner: PLAYER_ADDRESS,
+            loser: OPPONENT_ADDRESS,
+            draw: false,
+            score: None,
+            challenge: None,
+        });
+        ctx.set_parameter(&parameter_bytes);
+
+        // Set up the state and host.
+        let mut state_builder = TestStateBuilder::new();
+        let mut state = initial_state(&mut state_builder);
+        add_player(&mut state, PLAYER_ADDRESS);
+        add_player(&mut state, OPPONENT_ADDRESS);
+        let mut host = TestHost::new(state, state_builder);
+
+        // Report the battle, then retry the same report.
+        let result: ContractResult<()> = contract_report_match(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Results in rejection");
+        let result: ContractResult<()> = contract_report_match(&ctx, &mut host, &mut logger);
+
+        // Check that the retry failed and counted nothing.
+        claim_eq!(
+            result,
+            Err(ContractError::BattleAlreadyReported),
+            "Repeated report should be rejected"
+        );
+        let winner_data = host.state().player_data.get(&PLAYER_ADDRESS).unwrap();
+        claim_eq!(winner_data.wins, 1, "Win should be counted once");
+    }
+
+    /// Test that a drawn match with a score is recorded for both players.
+    #[concordium_test]
+    fn test_report_match_draw_with_score() {
+        // Set up the context.
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(ADMIN_ADDRESS);
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(1));
+        let mut logger = TestLogger::init();
+
+        // Set up the parameter.
+        let parameter_bytes = to_bytes(&ReportMatchParams {
+            battle_id: 1,
+            winner: PLAYER_ADDRESS,
+            loser: OPPONENT_ADDRESS,
+            draw: true,
+            score: Some(MatchScore {
+                score_for: 2,
+                score_against: 2,
+            }),
+            challenge: None,
+        });
+        ctx.set_parameter(&parameter_bytes);
+
+        // Set up the state and host.
+        let mut state_builder = TestStateBuilder::new();
+        let mut state = initial_state(&mut state_builder);
+        add_player(&mut state, PLAYER_ADDRESS);
+        add_player(&mut state, OPPONENT_ADDRESS);
+        let mut host = TestHost::new(state, state_builder);
+
+        // Call the contract function.
+        let result: ContractResult<()> = contract_report_match(&ctx, &mut host, &mut logger);
+
+        // Check the result.
+        claim!(result.is_ok(), "Results in rejection");
+
+        // Check both players.
+        for player in [PLAYER_ADDRESS, OPPONENT_ADDRESS].iter() {
+            let player_data = host.state().player_data.get(player).unwrap();
+            claim_eq!(player_data.draws, 1, "Player should have one draw");
+            claim_eq!(player_data.wins + player_data.losses, 0, "Player should have no result");
+            claim_eq!(player_data.score_for, 2, "Player should have scored 2");
+            claim_eq!(player_data.score_against, 2, "Player should have conceded 2");
+            claim_eq!(player_data.rating, DEFAULT_RATING, "Draw should not change equal ratings");
+        }
+    }
+
+    /// Test that a score contradicting the result is rejected.
+    #[concordium_test]
+    fn test_update_battle_result_invalid_score() {
+        // Set up the context.
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+        ctx.set_sender(ADMIN_ADDRESS);
+        let mut logger = TestLogger::init();
+
+        // Set up the parameter with a win the player lost 2-3.
+        let parameter_bytes = to_bytes(&UpdateBattleResultParams {
+            player: PLAYER_ADDRESS,
+            result: BattleResult::Win,
+            score: Some(MatchScore {
+                score_for: 2,
+                score_against: 3,
+            }),
+            opponent: None,
+        });
+        ctx.set_parameter(&parameter_bytes);
+
+        // Set up the state and host.
+        let mut state_builder = TestStateBuilder::new();
+        let mut state = initial_state(&mut state_builder);
+        add_player(&mut state, PLAYER_ADDRESS);
+        let mut host = TestHost::new(state, state_builder);
+
+        // Call the contract function.
+        let result: ContractResult<()> = update_battle_result(&ctx, &mut host, &mut logger);
+
+        // Check that invoke failed.
+        claim_eq!(result, Err(ContractError::InvalidScore), "Score should agree with the result");
+    }
+
+    /// Test that results are recorded per season and archived when the
+    /// season ends, while lifetime totals keep accumulating.
+    #[concordium_test]
+    fn test_season_rollover() {
+        // Set up the context.
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(ADMIN_ADDRESS);
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(1));
+        let mut logger = TestLogger::init();
+
+        // Set up the state and host.
+        let mut state_builder = TestStateBuilder::new();
+        let mut state = initial_state(&mut state_builder);
+        add_player(&mut state, PLAYER_ADDRESS);
+        add_player(&mut state, OPPONENT_ADDRESS);
+        let mut host = TestHost::new(state, state_builder);
+
+        // Start the first season and report a battle in it.
+        let result: ContractResult<()> = contract_start_season(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Results in rejection");
+        let parameter_bytes = to_bytes(&ReportMatchParams {
+            battle_id: 1,
+            winner: PLAYER_ADDRESS,
+            loser: OPPONENT_ADDRESS,
+            draw: false,
+            score: None,
+            challenge: None,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_report_match(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Results in rejection");
+
+        // End the first season and start the second one.
+        let parameter_bytes = to_bytes(&EndSeasonParams {
+            start_next: true,
+            payouts: Vec::new(),
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_end_season(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Results in rejection");
+        claim_eq!(host.state().current_season, 2, "Second season should be in progress");
+
+        // Report a battle in the second season.
+        let parameter_bytes = to_bytes(&ReportMatchParams {
+            battle_id: 2,
+            winner: PLAYER_ADDRESS,
+            loser: OPPONENT_ADDRESS,
+            draw: false,
+            score: None,
+            challenge: None,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_report_match(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Results in rejection");
+
+        // Check the archived season, the new season and the lifetime totals.
+        let first_season = host.state().seasons.get(&1).unwrap();
+        claim!(first_season.ended_at.is_some(), "First season should be ended");
+        claim_eq!(first_season.players, 2, "First season should have two players");
+        let season_data = host.state().get_season_player(1, &PLAYER_ADDRESS).unwrap();
+        claim_eq!(season_data.wins, 1, "Player should have one win in the first season");
+        let season_data = host.state().get_season_player(2, &PLAYER_ADDRESS).unwrap();
+        claim_eq!(season_data.wins, 1, "Player should have one win in the second season");
+        claim_eq!(
+            season_data.rating.rating,
+            1_516_000_000,
+            "Season rating should start from the initial rating"
+        );
+        let player_data = host.state().get_player(&PLAYER_ADDRESS).unwrap();
+        claim_eq!(player_data.wins, 2, "Lifetime wins should accumulate across seasons");
+    }
+
+    /// Test that a season cannot be started while another is in progress.
+    #[concordium_test]
+    fn test_start_season_in_progress() {
+        // Set up the context.
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(ADMIN_ADDRESS);
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(1));
+        let mut logger = TestLogger::init();
+
+        // Set up the state and host.
+        let mut state_builder = TestStateBuilder::new();
+        let state = initial_state(&mut state_builder);
+        let mut host = TestHost::new(state, state_builder);
+
+        // Call the contract function twice.
+        let result: ContractResult<()> = contract_start_season(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Results in rejection");
+        let result: ContractResult<()> = contract_start_season(&ctx, &mut host, &mut logger);
+
+        // Check that the second invoke failed.
+        claim_eq!(
+            result,
+            Err(ContractError::SeasonInProgress),
+            "Season should not start while another is in progress"
+        );
+    }
+
+    /// Test that a granted reporter can report battles but not pause the
+    /// contract, and loses the ability once the role is revoked.
+    #[concordium_test]
+    fn test_reporter_role() {
+        // Set up the context.
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(ADMIN_ADDRESS);
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(1));
+        let mut logger = TestLogger::init();
+
+        // Set up the state and host.
+        let mut state_builder = TestStateBuilder::new();
+        let mut state = initial_state(&mut state_builder);
+        add_player(&mut state, PLAYER_ADDRESS);
+        add_player(&mut state, OPPONENT_ADDRESS);
+        let mut host = TestHost::new(state, state_builder);
+
+        // Grant the reporter role to the game server.
+        let role_bytes = to_bytes(&RoleParams {
+            address: REPORTER_ADDRESS,
+            role: Role::Reporter,
+        });
+        ctx.set_parameter(&role_bytes);
+        let result: ContractResult<()> = contract_grant_role(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Results in rejection");
+        claim!(
+            host.state().has_role(&REPORTER_ADDRESS, Role::Reporter),
+            "Game server should be a reporter"
+        );
+
+        // Report a battle as the game server.
+        ctx.set_sender(REPORTER_ADDRESS);
+        let parameter_bytes = to_bytes(&ReportMatchParams {
+            battle_id: 1,
+            winner: PLAYER_ADDRESS,
+            loser: OPPONENT_ADDRESS,
+            draw: false,
+            score: None,
+            challenge: None,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_report_match(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Reporter should be able to report battles");
+
+        // Try to pause the contract as the game server.
+        let parameter_bytes = to_bytes(&true);
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_set_paused(&ctx, &mut host, &mut logger);
+        claim_eq!(result, Err(ContractError::Unauthorized), "Reporter should not be able to pause");
+
+        // Revoke the reporter role and report again.
+        ctx.set_sender(ADMIN_ADDRESS);
+        ctx.set_parameter(&role_bytes);
+        let result: ContractResult<()> = contract_revoke_role(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Results in rejection");
+        ctx.set_sender(REPORTER_ADDRESS);
+        let parameter_bytes = to_bytes(&ReportMatchParams {
+            battle_id: 2,
+            winner: PLAYER_ADDRESS,
+            loser: OPPONENT_ADDRESS,
+            draw: false,
+            score: None,
+            challenge: None,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_report_match(&ctx, &mut host, &mut logger);
+        claim_eq!(
+            result,
+            Err(ContractError::Unauthorized),
+            "Revoked reporter should not be able to report battles"
+        );
+    }
+
+    /// Test that only admins can grant roles.
+    #[concordium_test]
+    fn test_grant_role_not_authorized() {
+        // Set up the context.
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(REPORTER_ADDRESS);
+        let mut logger = TestLogger::init();
+
+        // Set up the parameter.
+        let parameter_bytes = to_bytes(&RoleParams {
+            address: REPORTER_ADDRESS,
+            role: Role::Admin,
+        });
+        ctx.set_parameter(&parameter_bytes);
+
+        // Set up the state and host.
+        let mut state_builder = TestStateBuilder::new();
+        let state = initial_state(&mut state_builder);
+        let mut host = TestHost::new(state, state_builder);
+
+        // Call the contract function.
+        let result: ContractResult<()> = contract_grant_role(&ctx, &mut host, &mut logger);
+
+        // Check that invoke failed.
+        claim_eq!(result, Err(ContractError::Unauthorized), "Only admins can grant roles");
+        claim!(
+            !host.state().has_role(&REPORTER_ADDRESS, Role::Admin),
+            "Role should not be granted"
+        );
+    }
+
+    /// Test that adding a player and changing its state log the matching
+    /// events.
+    #[concordium_test]
+    fn test_set_player_data_events() {
+        // Set up the context.
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+        ctx.set_sender(ADMIN_ADDRESS);
+        let mut logger = TestLogger::init();
+
+        // Set up the state and host.
+        let mut state_builder = TestStateBuilder::new();
+        let state = initial_state(&mut state_builder);
+        let mut host = TestHost::new(state, state_builder);
+
+        // Add the player.
+        let parameter_bytes = to_bytes(&(PLAYER_ADDRESS, PlayerState::Active));
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> =
+            contract_state_set_player_data(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Results in rejection");
+
+        // Suspend the player.
+        let parameter_bytes = to_bytes(&(PLAYER_ADDRESS, PlayerState::Suspended));
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> =
+            contract_state_set_player_data(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Results in rejection");
+
+        // Check the events.
+        claim_eq!(
+            logger.logs,
+            vec![
+                to_bytes(&VersusEvent::PlayerRegistered(PlayerRegisteredEvent {
+                    player: PLAYER_ADDRESS,
+                    state: PlayerState::Active,
+                })),
+                to_bytes(&VersusEvent::PlayerStateChanged(PlayerStateChangedEvent {
+                    player: PLAYER_ADDRESS,
+                    state: PlayerState::Suspended,
+                })),
+            ],
+            "Events should be logged with their tags"
+        );
+        claim_eq!(logger.logs[0][0], PLAYER_REGISTERED_EVENT_TAG, "Event should start with its tag");
+    }
+
+    /// Test that a skip-invalid batch applies the valid results and reports
+    /// the skipped ones.
+    #[concordium_test]
+    fn test_update_battle_result_batch_skip_invalid() {
+        // Set up the context.
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+        ctx.set_sender(ADMIN_ADDRESS);
+        let mut logger = TestLogger::init();
+
+        // Set up the parameter with a result for a player that was not added.
+        let parameter_bytes = to_bytes(&UpdateBattleResultBatchParams {
+            mode: BatchMode::SkipInvalid,
+            updates: vec![
+                UpdateBattleResultParams {
+                    player: PLAYER_ADDRESS,
+                    result: BattleResult::Win,
+                    score: None,
+                    opponent: None,
+                },
+                UpdateBattleResultParams {
+                    player: NEW_ADMIN_ADDRESS,
+                    result: BattleResult::Loss,
+                    score: None,
+                    opponent: None,
+                },
+                UpdateBattleResultParams {
+                    player: OPPONENT_ADDRESS,
+                    result: BattleResult::Loss,
+                    score: None,
+                    opponent: None,
+                },
+            ],
+        });
+        ctx.set_parameter(&parameter_bytes);
+
+        // Set up the state and host.
+        let mut state_builder = TestStateBuilder::new();
+        let mut state = initial_state(&mut state_builder);
+        add_player(&mut state, PLAYER_ADDRESS);
+        add_player(&mut state, OPPONENT_ADDRESS);
+        let mut host = TestHost::new(state, state_builder);
+
+        // Call the contract function.
+        let result = contract_update_battle_result_batch(&ctx, &mut host, &mut logger);
+
+        // Check the outcome of each item.
+        claim_eq!(
+            result,
+            Ok(vec![
+                BatchItemOutcome::Applied,
+                BatchItemOutcome::Skipped(ContractError::PlayerDoesNotExist),
+                BatchItemOutcome::Applied,
+            ]),
+            "Only the unknown player should be skipped"
+        );
+        claim_eq!(logger.logs.len(), 2, "Each applied item should log its own event");
+        claim_eq!(host.state().get_player(&PLAYER_ADDRESS).unwrap().wins, 1, "Win should count");
+        claim_eq!(
+            host.state().get_player(&OPPONENT_ADDRESS).unwrap().losses,
+            1,
+            "Loss should count"
+        );
+    }
+
+    /// Test that an all-or-nothing batch rejects if one item is invalid.
+    #[concordium_test]
+    fn test_update_battle_result_batch_all_or_nothing() {
+        // Set up the context.
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+        ctx.set_sender(ADMIN_ADDRESS);
+        let mut logger = TestLogger::init();
+
+        // Set up the parameter.
+        let parameter_bytes = to_bytes(&UpdateBattleResultBatchParams {
+            mode: BatchMode::AllOrNothing,
+            updates: vec![
+                UpdateBattleResultParams {
+                    player: PLAYER_ADDRESS,
+                    result: BattleResult::Win,
+                    score: None,
+                    opponent: None,
+                },
+                UpdateBattleResultParams {
+                    player: NEW_ADMIN_ADDRESS,
+                    result: BattleResult::Loss,
+                    score: None,
+                    opponent: None,
+                },
+            ],
+        });
+        ctx.set_parameter(&parameter_bytes);
+
+        // Set up the state and host.
+        let mut state_builder = TestStateBuilder::new();
+        let mut state = initial_state(&mut state_builder);
+        add_player(&mut state, PLAYER_ADDRESS);
+        let mut host = TestHost::new(state, state_builder);
+
+        // Call the contract function.
+        let result = contract_update_battle_result_batch(&ctx, &mut host, &mut logger);
+
+        // Check that invoke failed.
+        claim_eq!(
+            result,
+            Err(ContractError::PlayerDoesNotExist),
+            "Batch should be rejected as a whole"
+        );
+    }
+
+    /// Test that a player can register by paying the entry fee, which is
+    /// added to the treasury.
+    #[concordium_test]
+    fn test_register_with_fee() {
+        // Set up the context.
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+        ctx.set_sender(PLAYER_ADDRESS);
+        let mut logger = TestLogger::init();
+
+        // Set up the state and host.
+        let mut state_builder = TestStateBuilder::new();
+        let mut state = initial_state(&mut state_builder);
+        state.registration = RegistrationConfig {
+            fee: RegistrationFee::Fixed(Amount::from_ccd(10)),
+            requires_approval: true,
+        };
+        let mut host = TestHost::new(state, state_builder);
+
+        // Register with the wrong amount.
+        let result: ContractResult<()> =
+            contract_register(&ctx, &mut host, Amount::from_ccd(5), &mut logger);
+        claim_eq!(result, Err(ContractError::WrongAmount), "Fee should be paid in full");
+
+        // Register with the entry fee.
+        let result: ContractResult<()> =
+            contract_register(&ctx, &mut host, Amount::from_ccd(10), &mut logger);
+        claim!(result.is_ok(), "Results in rejection");
+
+        // Check the player and the treasury.
+        claim_eq!(
+            host.state().get_player(&PLAYER_ADDRESS).unwrap().state,
+            PlayerState::PendingApproval,
+            "Player should wait for approval"
+        );
+        claim_eq!(host.state().treasury, Amount::from_ccd(10), "Fee should go to the treasury");
+
+        // Register again.
+        let result: ContractResult<()> =
+            contract_register(&ctx, &mut host, Amount::from_ccd(10), &mut logger);
+        claim_eq!(
+            result,
+            Err(ContractError::PlayerAlreadyExists),
+            "Player should only register once"
+        );
+    }
+
+    /// Test that registration is closed by default.
+    #[concordium_test]
+    fn test_register_closed() {
+        // Set up the context.
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+        ctx.set_sender(PLAYER_ADDRESS);
+        let mut logger = TestLogger::init();
+
+        // Set up the state and host.
+        let mut state_builder = TestStateBuilder::new();
+        let state = initial_state(&mut state_builder);
+        let mut host = TestHost::new(state, state_builder);
+
+        // Call the contract function.
+        let result: ContractResult<()> =
+            contract_register(&ctx, &mut host, Amount::zero(), &mut logger);
+
+        // Check that invoke failed.
+        claim_eq!(result, Err(ContractError::RegistrationClosed), "Registration should be closed");
+    }
+
+    /// Test that the admin can withdraw from the treasury.
+    #[concordium_test]
+    fn test_withdraw_treasury() {
+        // Set up the context.
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(ADMIN_ADDRESS);
+        let mut logger = TestLogger::init();
+
+        // Set up the parameter.
+        let parameter_bytes = to_bytes(&WithdrawTreasuryParams {
+            to: ADMIN_ACCOUNT,
+            amount: Amount::from_ccd(4),
+        });
+        ctx.set_parameter(&parameter_bytes);
+
+        // Set up the state and host.
+        let mut state_builder = TestStateBuilder::new();
+        let mut state = initial_state(&mut state_builder);
+        state.treasury = Amount::from_ccd(10);
+        let mut host = TestHost::new(state, state_builder);
+        host.set_self_balance(Amount::from_ccd(10));
+
+        // Call the contract function.
+        let result: ContractResult<()> = contract_withdraw_treasury(&ctx, &mut host, &mut logger);
+
+        // Check the result.
+        claim!(result.is_ok(), "Results in rejection");
+        claim_eq!(host.state().treasury, Amount::from_ccd(6), "Treasury should be reduced");
+        claim_eq!(
+            host.get_transfers(),
+            [(ADMIN_ACCOUNT, Amount::from_ccd(4))],
+            "Withdrawal should be transferred to the admin"
+        );
+    }
+
+    /// Test that a challenge locks both stakes and that reporting the battle
+    /// pays the pot to the winner minus the wager fee.
+    #[concordium_test]
+    fn test_challenge_settled() {
+        // Set up the context.
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(100));
+        ctx.set_sender(PLAYER_ADDRESS);
+        let mut logger = TestLogger::init();
+
+        // Set up the state and host.
+        let mut state_builder = TestStateBuilder::new();
+        let mut state = initial_state(&mut state_builder);
+        state.wager_fee = 500;
+        add_player(&mut state, PLAYER_ADDRESS);
+        add_player(&mut state, OPPONENT_ADDRESS);
+        let mut host = TestHost::new(state, state_builder);
+
+        // Create the challenge.
+        let parameter_bytes = to_bytes(&CreateChallengeParams {
+            opponent: OPPONENT_ACCOUNT,
+            expiry: Timestamp::from_timestamp_millis(1000),
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let result = contract_create_challenge(&ctx, &mut host, Amount::from_ccd(10), &mut logger);
+        claim_eq!(result, Ok(0), "Challenge should be created");
+
+        // Accept the challenge.
+        ctx.set_sender(OPPONENT_ADDRESS);
+        let parameter_bytes = to_bytes(&0u64);
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> =
+            contract_accept_challenge(&ctx, &mut host, Amount::from_ccd(10), &mut logger);
+        claim!(result.is_ok(), "Results in rejection");
+        host.set_self_balance(Amount::from_ccd(20));
+
+        // Report the battle.
+        ctx.set_sender(ADMIN_ADDRESS);
+        let parameter_bytes = to_bytes(&ReportMatchParams {
+            battle_id: 1,
+            winner: OPPONENT_ADDRESS,
+            loser: PLAYER_ADDRESS,
+            draw: false,
+            score: None,
+            challenge: Some(0),
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_report_match(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Results in rejection");
+
+        // Check the payout and the fee.
+        claim_eq!(
+            host.get_transfers(),
+            [(OPPONENT_ACCOUNT, Amount::from_ccd(19))],
+            "Pot minus the fee should be paid to the winner"
+        );
+        claim_eq!(host.state().treasury, Amount::from_ccd(1), "Fee should go to the treasury");
+        claim_eq!(
+            host.state().challenges.get(&0).unwrap().status,
+            ChallengeStatus::Settled,
+            "Challenge should be settled"
+        );
+        claim!(
+            logger.logs.contains(&to_bytes(&VersusEvent::ChallengeSettled(ChallengeSettledEvent {
+                challenge: 0,
+                battle_id: 1,
+                winner: Some(OPPONENT_ACCOUNT),
+                payout: Amount::from_ccd(19),
+                fee: Amount::from_ccd(1),
+            }))),
+            "Missing event for the settled challenge"
+        );
+    }
+
+    /// Test that a challenge can only be settled by a battle between its
+    /// players.
+    #[concordium_test]
+    fn test_challenge_mismatch() {
+        // Set up the context.
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(100));
+        ctx.set_sender(ADMIN_ADDRESS);
+        let mut logger = TestLogger::init();
+
+        // Set up the state and host.
+        let mut state_builder = TestStateBuilder::new();
+        let mut state = initial_state(&mut state_builder);
+        add_player(&mut state, PLAYER_ADDRESS);
+        add_player(&mut state, OPPONENT_ADDRESS);
+        add_player(&mut state, REPORTER_ADDRESS);
+        state.challenges.insert(0, Challenge {
+            challenger: PLAYER_ACCOUNT,
+            opponent: OPPONENT_ACCOUNT,
+            stake: Amount::from_ccd(10),
+            expiry: Timestamp::from_timestamp_millis(1000),
+            status: ChallengeStatus::Accepted,
+        });
+        let mut host = TestHost::new(state, state_builder);
+
+        // Report a battle against another player.
+        let parameter_bytes = to_bytes(&ReportMatchParams {
+            battle_id: 1,
+            winner: PLAYER_ADDRESS,
+            loser: REPORTER_ADDRESS,
+            draw: false,
+            score: None,
+            challenge: Some(0),
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_report_match(&ctx, &mut host, &mut logger);
+
+        // Check that invoke failed.
+        claim_eq!(
+            result,
+            Err(ContractError::ChallengeMismatch),
+            "Challenge should only be settled by its players"
+        );
+    }
+
+    /// Test that the stakes of an accepted challenge can be refunded once it
+    /// has expired.
+    #[concordium_test]
+    fn test_refund_challenge() {
+        // Set up the context.
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(500));
+        ctx.set_sender(REPORTER_ADDRESS);
+        let mut logger = TestLogger::init();
+
+        // Set up the parameter.
+        let parameter_bytes = to_bytes(&0u64);
+        ctx.set_parameter(&parameter_bytes);
+
+        // Set up the state and host.
+        let mut state_builder = TestStateBuilder::new();
+        let mut state = initial_state(&mut state_builder);
+        state.challenges.insert(0, Challenge {
+            challenger: PLAYER_ACCOUNT,
+            opponent: OPPONENT_ACCOUNT,
+            stake: Amount::from_ccd(10),
+            expiry: Timestamp::from_timestamp_millis(1000),
+            status: ChallengeStatus::Accepted,
+        });
+        let mut host = TestHost::new(state, state_builder);
+        host.set_self_balance(Amount::from_ccd(20));
+
+        // Refund before the expiry.
+        let result: ContractResult<()> = contract_refund_challenge(&ctx, &mut host, &mut logger);
+        claim_eq!(
+            result,
+            Err(ContractError::ChallengeNotExpired),
+            "Accepted challenge should not be refunded before the expiry"
+        );
+
+        // Refund after the expiry.
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(1000));
+        let result: ContractResult<()> = contract_refund_challenge(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Results in rejection");
+        claim_eq!(
+            host.get_transfers(),
+            [(PLAYER_ACCOUNT, Amount::from_ccd(10)), (OPPONENT_ACCOUNT, Amount::from_ccd(10))],
+            "Both stakes should be refunded"
+        );
+        claim_eq!(
+            host.state().challenges.get(&0).unwrap().status,
+            ChallengeStatus::Refunded,
+            "Challenge should be refunded"
+        );
+    }
+
+    /// Test that tokens from an allowed token contract are deposited into
+    /// the prize pool named in the additional data.
+    #[concordium_test]
+    fn test_on_receiving_cis2() {
+        // Set up the context.
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(TOKEN_CONTRACT));
+        let mut logger = TestLogger::init();
+
+        // Set up the parameter.
+        let parameter_bytes = to_bytes(&OnReceivingCis2Params {
+            token_id: TokenIdVec(vec![1]),
+            amount: TokenAmountU64(100),
+            from: ADMIN_ADDRESS,
+            data: AdditionalData::from(to_bytes(&String::from("season-1"))),
+        });
+        ctx.set_parameter(&parameter_bytes);
+
+        // Set up the state and host.
+        let mut state_builder = TestStateBuilder::new();
+        let state = initial_state(&mut state_builder);
+        let mut host = TestHost::new(state, state_builder);
+
+        // Deposit before the token contract is allowed.
+        let result: ContractResult<()> = contract_on_receiving_cis2(&ctx, &mut host, &mut logger);
+        claim_eq!(result, Err(ContractError::TokenNotAllowed), "Token should not be allowed");
+
+        // Deposit after the token contract is allowed.
+        host.state_mut().prize_tokens.insert(TOKEN_CONTRACT);
+        let result: ContractResult<()> = contract_on_receiving_cis2(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Results in rejection");
+
+        // Check the prize pool.
+        let key = PrizePoolKey {
+            pool: String::from("season-1"),
+            token_contract: TOKEN_CONTRACT,
+            token_id: TokenIdVec(vec![1]),
+        };
+        claim_eq!(
+            host.state().prize_pools.get(&key).map(|balance| *balance),
+            Some(TokenAmountU64(100)),
+            "Tokens should be added to the prize pool"
+        );
+
+        // Deposit an amount that overflows the prize pool.
+        host.state_mut().prize_pools.insert(key.clone(), TokenAmountU64(u64::MAX));
+        let result: ContractResult<()> = contract_on_receiving_cis2(&ctx, &mut host, &mut logger);
+        claim_eq!(result, Err(ContractError::PrizePoolOverflow), "Deposit should overflow");
+    }
+
+    /// Test that the admin can pay out a prize, which invokes `transfer` on
+    /// the token contract.
+    #[concordium_test]
+    fn test_payout_prize() {
+        // Set up the context.
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(ADMIN_ADDRESS);
+        ctx.set_self_address(ContractAddress {
+            index: 1,
+            subindex: 0,
+        });
+        let mut logger = TestLogger::init();
+
+        // Set up the state and host.
+        let key = PrizePoolKey {
+            pool: String::from("season-1"),
+            token_contract: TOKEN_CONTRACT,
+            token_id: TokenIdVec(vec![1]),
+        };
+        let mut state_builder = TestStateBuilder::new();
+        let mut state = initial_state(&mut state_builder);
+        state.prize_pools.insert(key.clone(), TokenAmountU64(100));
+        let mut host = TestHost::new(state, state_builder);
+        host.setup_mock_entrypoint(
+            TOKEN_CONTRACT,
+            OwnedEntrypointName::new_unchecked("transfer".into()),
+            MockFn::returning_ok(()),
+        );
+
+        // Pay out more than the prize pool holds.
+        let parameter_bytes = to_bytes(&PrizePayout {
+            key: key.clone(),
+            amount: TokenAmountU64(150),
+            to: Receiver::Account(PLAYER_ACCOUNT),
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_payout_prize(&ctx, &mut host, &mut logger);
+        claim_eq!(
+            result,
+            Err(ContractError::InsufficientFunds),
+            "Payout should not exceed the prize pool"
+        );
+
+        // Pay out part of the prize pool.
+        let parameter_bytes = to_bytes(&PrizePayout {
+            key: key.clone(),
+            amount: TokenAmountU64(60),
+            to: Receiver::Account(PLAYER_ACCOUNT),
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_payout_prize(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Results in rejection");
+        claim_eq!(
+            host.state().prize_pools.get(&key).map(|balance| *balance),
+            Some(TokenAmountU64(40)),
+            "Payout should be deducted from the prize pool"
+        );
+    }
+
+    /// Test that the leaderboard is kept sorted as results are reported.
+    #[concordium_test]
+    fn test_leaderboard_updated() {
+        // Set up the context.
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+        ctx.set_sender(ADMIN_ADDRESS);
+        let mut logger = TestLogger::init();
+
+        // Set up the state and host.
+        let mut state_builder = TestStateBuilder::new();
+        let mut state = initial_state(&mut state_builder);
+        add_player(&mut state, PLAYER_ADDRESS);
+        add_player(&mut state, OPPONENT_ADDRESS);
+        let mut host = TestHost::new(state, state_builder);
+
+        // Report two battles won by the opponent.
+        for battle_id in 1..=2 {
+            let parameter_bytes = to_bytes(&ReportMatchParams {
+                battle_id,
+                winner: OPPONENT_ADDRESS,
+                loser: PLAYER_ADDRESS,
+                draw: false,
+                score: None,
+                challenge: None,
+            });
+            ctx.set_parameter(&parameter_bytes);
+            let result: ContractResult<()> = contract_report_match(&ctx, &mut host, &mut logger);
+            claim!(result.is_ok(), "Results in rejection");
+        }
+
+        // Check the leaderboard.
+        let leaderboard = contract_get_leaderboard(&ctx, &host).unwrap();
+        claim_eq!(leaderboard.len(), 2, "Both players should be ranked");
+        claim_eq!(leaderboard[0].rank, 1, "Ranks should start from 1");
+        claim_eq!(leaderboard[0].player, OPPONENT_ADDRESS, "Winner should be ranked first");
+        claim_eq!(leaderboard[0].stats.wins, 2, "Stats should be returned with the rank");
+        claim_eq!(leaderboard[1].player, PLAYER_ADDRESS, "Loser should be ranked second");
+    }
+
+    /// Test that changing the leaderboard settings ranks every player again.
+    #[concordium_test]
+    fn test_set_leaderboard() {
+        // Set up the context.
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(ADMIN_ADDRESS);
+        let mut logger = TestLogger::init();
+
+        // Set up the state and host.
+        let mut state_builder = TestStateBuilder::new();
+        let mut state = initial_state(&mut state_builder);
+        add_player(&mut state, PLAYER_ADDRESS);
+        add_player(&mut state, OPPONENT_ADDRESS);
+        state.player_data.get_mut(&OPPONENT_ADDRESS).unwrap().wins = 3;
+        let mut host = TestHost::new(state, state_builder);
+
+        // Set a size that is too large.
+        let parameter_bytes = to_bytes(&LeaderboardConfig {
+            ranking: LeaderboardRanking::Wins,
+            size: MAX_LEADERBOARD_SIZE + 1,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_set_leaderboard(&ctx, &mut host, &mut logger);
+        claim_eq!(
+            result,
+            Err(ContractError::InvalidLeaderboardSize),
+            "Leaderboard size should be bounded"
+        );
+
+        // Rank the single best player by wins.
+        let parameter_bytes = to_bytes(&LeaderboardConfig {
+            ranking: LeaderboardRanking::Wins,
+            size: 1,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_set_leaderboard(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Results in rejection");
+        claim_eq!(
+            *host.state().leaderboard.get(),
+            vec![LeaderboardEntry {
+                player: OPPONENT_ADDRESS,
+                key: 3,
+            }],
+            "Leaderboard should be rebuilt from all players"
+        );
+    }
+
+    /// Test that players can be listed page by page and filtered by state.
+    #[concordium_test]
+    fn test_get_players() {
+        // Set up the context.
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+
+        // Set up the state and host.
+        let mut state_builder = TestStateBuilder::new();
+        let mut state = initial_state(&mut state_builder);
+        add_player(&mut state, PLAYER_ADDRESS);
+        add_player(&mut state, OPPONENT_ADDRESS);
+        add_player(&mut state, REPORTER_ADDRESS);
+        state.player_data.get_mut(&OPPONENT_ADDRESS).unwrap().state = PlayerState::Suspended;
+        let host = TestHost::new(state, state_builder);
+
+        // Get the first page.
+        let parameter_bytes = to_bytes(&GetPlayersParams {
+            cursor: None,
+            limit: 2,
+            state: None,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let first_page = contract_get_players(&ctx, &host).unwrap();
+        claim_eq!(first_page.players.len(), 2, "Page should be full");
+        claim_eq!(
+            first_page.next_cursor,
+            Some(first_page.players[1].player),
+            "Next page should follow the last player"
+        );
+        claim_eq!(first_page.player_count, 3, "All players should be counted");
+
+        // Get the last page.
+        let parameter_bytes = to_bytes(&GetPlayersParams {
+            cursor: first_page.next_cursor,
+            limit: 2,
+            state: None,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let page = contract_get_players(&ctx, &host).unwrap();
+        claim_eq!(page.players.len(), 1, "Last page should hold the remaining player");
+        claim_eq!(page.next_cursor, None, "No page should follow");
+        claim!(
+            first_page.players.iter().all(|entry| entry.player != page.players[0].player),
+            "Pages should not overlap"
+        );
+
+        // Get only the suspended players.
+        let parameter_bytes = to_bytes(&GetPlayersParams {
+            cursor: None,
+            limit: 10,
+            state: Some(PlayerState::Suspended),
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let page = contract_get_players(&ctx, &host).unwrap();
+        claim_eq!(page.players.len(), 1, "Only suspended players should be listed");
+        claim_eq!(page.players[0].player, OPPONENT_ADDRESS, "Opponent should be listed");
+
+        // Get an empty page.
+        let parameter_bytes = to_bytes(&GetPlayersParams {
+            cursor: first_page.next_cursor,
+            limit: 0,
+            state: None,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let result = contract_get_players(&ctx, &host);
+        claim!(
+            matches!(result, Err(ContractError::InvalidPageSize)),
+            "Page size should not be zero"
+        );
+    }
+
+    /// Test that the stats of a player include the derived values and the
+    /// time of the latest battle.
+    #[concordium_test]
+    fn test_get_player_stats() {
+        // Set up the context.
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(100));
+        ctx.set_sender(ADMIN_ADDRESS);
+        let mut logger = TestLogger::init();
+
+        // Set up the state and host.
+        let mut state_builder = TestStateBuilder::new();
+        let mut state = initial_state(&mut state_builder);
+        add_player(&mut state, PLAYER_ADDRESS);
+        add_player(&mut state, OPPONENT_ADDRESS);
+        let mut host = TestHost::new(state, state_builder);
+
+        // Report a win and a draw.
+        for (battle_id, draw) in [(1, false), (2, true)] {
+            let parameter_bytes = to_bytes(&ReportMatchParams {
+                battle_id,
+                winner: PLAYER_ADDRESS,
+                loser: OPPONENT_ADDRESS,
+                draw,
+                score: None,
+                challenge: None,
+            });
+            ctx.set_parameter(&parameter_bytes);
+            let result: ContractResult<()> = contract_report_match(&ctx, &mut host, &mut logger);
+            claim!(result.is_ok(), "Results in rejection");
+        }
+
+        // Get the stats.
+        let parameter_bytes = to_bytes(&PLAYER_ADDRESS);
+        ctx.set_parameter(&parameter_bytes);
+        let stats = contract_get_player_stats(&ctx, &host).unwrap();
+
+        // Check the stats.
+        claim_eq!(stats.version, PLAYER_STATS_VERSION, "Stats should carry their version");
+        claim_eq!(stats.games_played, 2, "Player should have played two games");
+        claim_eq!(stats.win_rate, 5_000, "Player should have won half of the games");
+        claim_eq!(
+            stats.registered_at,
+            Timestamp::from_timestamp_millis(0),
+            "Registration time should be kept"
+        );
+        claim_eq!(
+            stats.last_played,
+            Some(Timestamp::from_timestamp_millis(100)),
+            "Time of the latest battle should be recorded"
+        );
+    }
+
+    /// Test that the stats of a player that has not been added are rejected.
+    #[concordium_test]
+    fn test_get_player_stats_not_added() {
+        // Set up the context.
+        let mut ctx = TestReceiveContext::empty();
+        let parameter_bytes = to_bytes(&PLAYER_ADDRESS);
+        ctx.set_parameter(&parameter_bytes);
+
+        // Set up the state and host.
+        let mut state_builder = TestStateBuilder::new();
+        let state = initial_state(&mut state_builder);
+        let host = TestHost::new(state, state_builder);
+
+        // Call the contract function.
+        let result = contract_get_player_stats(&ctx, &host);
+
+        // Check that invoke failed.
+        claim!(
+            matches!(result, Err(ContractError::PlayerDoesNotExist)),
+            "Player should not exist"
+        );
+    }
+
+    /// Test that streaks are tracked and that reaching a milestone logs an
+    /// event.
+    #[concordium_test]
+    fn test_streak_milestone() {
+        // Set up the context.
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+        ctx.set_sender(ADMIN_ADDRESS);
+        let mut logger = TestLogger::init();
+
+        // Set up the state and host.
+        let mut state_builder = TestStateBuilder::new();
+        let mut state = initial_state(&mut state_builder);
+        state.streak_milestones = vec![2];
+        add_player(&mut state, PLAYER_ADDRESS);
+        add_player(&mut state, OPPONENT_ADDRESS);
+        let mut host = TestHost::new(state, state_builder);
+
+        // Report two wins of the player.
+        for battle_id in 1..=2 {
+            let parameter_bytes = to_bytes(&ReportMatchParams {
+                battle_id,
+                winner: PLAYER_ADDRESS,
+                loser: OPPONENT_ADDRESS,
+                draw: false,
+                score: None,
+                challenge: None,
+            });
+            ctx.set_parameter(&parameter_bytes);
+            let result: ContractResult<()> = contract_report_match(&ctx, &mut host, &mut logger);
+            claim!(result.is_ok(), "Results in rejection");
+        }
+
+        // Check the streaks and the events.
+        let player_data = host.state().get_player(&PLAYER_ADDRESS).unwrap();
+        claim_eq!(player_data.current_streak, 2, "Player should be on a win streak");
+        claim_eq!(player_data.best_win_streak, 2, "Best win streak should be recorded");
+        let opponent_data = host.state().get_player(&OPPONENT_ADDRESS).unwrap();
+        claim_eq!(opponent_data.current_streak, -2, "Opponent should be on a losing streak");
+        claim_eq!(opponent_data.worst_loss_streak, 2, "Worst losing streak should be recorded");
+        claim!(
+            logger.logs.contains(&to_bytes(&VersusEvent::StreakMilestone(StreakMilestoneEvent {
+                player: PLAYER_ADDRESS,
+                kind: StreakKind::Win,
+                streak: 2,
+            }))),
+            "Missing event for the win streak"
+        );
+        claim!(
+            logger.logs.contains(&to_bytes(&VersusEvent::StreakMilestone(StreakMilestoneEvent {
+                player: OPPONENT_ADDRESS,
+                kind: StreakKind::Loss,
+                streak: 2,
+            }))),
+            "Missing event for the losing streak"
+        );
+
+        // A draw ends the streaks but keeps the records.
+        let parameter_bytes = to_bytes(&ReportMatchParams {
+            battle_id: 3,
+            winner: PLAYER_ADDRESS,
+            loser: OPPONENT_ADDRESS,
+            draw: true,
+            score: None,
+            challenge: None,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_report_match(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Results in rejection");
+        let player_data = host.state().get_player(&PLAYER_ADDRESS).unwrap();
+        claim_eq!(player_data.current_streak, 0, "Draw should end the streak");
+        claim_eq!(player_data.best_win_streak, 2, "Best win streak should be kept");
+    }
+
+    /// Test that streak milestones must be strictly increasing.
+    #[concordium_test]
+    fn test_set_streak_milestones_invalid() {
+        // Set up the context.
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(ADMIN_ADDRESS);
+        let mut logger = TestLogger::init();
+
+        // Set up the parameter.
+        let parameter_bytes = to_bytes(&vec![10u64, 5]);
+        ctx.set_parameter(&parameter_bytes);
+
+        // Set up the state and host.
+        let mut state_builder = TestStateBuilder::new();
+        let state = initial_state(&mut state_builder);
+        let mut host = TestHost::new(state, state_builder);
+
+        // Call the contract function.
+        let result: ContractResult<()> =
+            contract_set_streak_milestones(&ctx, &mut host, &mut logger);
+
+        // Check that invoke failed.
+        claim_eq!(
+            result,
+            Err(ContractError::InvalidStreakMilestones),
+            "Milestones should be increasing"
+        );
+        claim_eq!(host.state().streak_milestones, vec![5, 10, 25], "Milestones should not change");
+    }
+
+    /// Test that a suspended player cannot record results and is reinstated
+    /// once the suspension has ended.
+    #[concordium_test]
+    fn test_timed_suspension() {
+        // Set up the context.
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(100));
+        ctx.set_sender(ADMIN_ADDRESS);
+        let mut logger = TestLogger::init();
+
+        // Set up the state and host.
+        let mut state_builder = TestStateBuilder::new();
+        let mut state = initial_state(&mut state_builder);
+        add_player(&mut state, PLAYER_ADDRESS);
+        let mut host = TestHost::new(state, state_builder);
+
+        // Suspend the player.
+        let parameter_bytes = to_bytes(&SuspendPlayerParams {
+            player: PLAYER_ADDRESS,
+            until: Timestamp::from_timestamp_millis(1000),
+            reason: 7,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_suspend_player(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Results in rejection");
+
+        // Check the remaining suspension time.
+        let parameter_bytes = to_bytes(&PLAYER_ADDRESS);
+        ctx.set_parameter(&parameter_bytes);
+        let status = contract_get_suspension(&ctx, &host).unwrap().unwrap();
+        claim_eq!(status.suspension.reason, 7, "Reason should be kept");
+        claim_eq!(status.suspension.moderator, ADMIN_ADDRESS, "Moderator should be kept");
+        claim_eq!(
+            status.remaining,
+            Duration::from_millis(900),
+            "Remaining time should be reported"
+        );
+
+        // Record a result during the suspension.
+        let parameter_bytes = to_bytes(&UpdateBattleResultParams {
+            player: PLAYER_ADDRESS,
+            result: BattleResult::Win,
+            score: None,
+            opponent: None,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = update_battle_result(&ctx, &mut host, &mut logger);
+        claim_eq!(
+            result,
+            Err(ContractError::PlayerSuspended),
+            "Suspended player should not record results"
+        );
+
+        // Check that the views report the player as reinstated once the
+        // suspension has ended.
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(1000));
+        let parameter_bytes = to_bytes(&PLAYER_ADDRESS);
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(
+            contract_state_get_player_data(&ctx, &host),
+            Ok(PlayerState::Active),
+            "Player should be reported as active"
+        );
+        claim_eq!(
+            contract_get_player_stats(&ctx, &host).map(|stats| stats.state),
+            Ok(PlayerState::Active),
+            "Stats should report the player as active"
+        );
+        let parameter_bytes = to_bytes(&GetPlayersParams {
+            cursor: None,
+            limit: 10,
+            state: Some(PlayerState::Active),
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let page = contract_get_players(&ctx, &host).unwrap();
+        claim_eq!(page.players.len(), 1, "Player should be listed as active");
+        claim_eq!(page.players[0].data.state, PlayerState::Active, "Player should be active");
+
+        // Record a result after the suspension.
+        let parameter_bytes = to_bytes(&UpdateBattleResultParams {
+            player: PLAYER_ADDRESS,
+            result: BattleResult::Win,
+            score: None,
+            opponent: None,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = update_battle_result(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Results in rejection");
+        let player_data = host.state().get_player(&PLAYER_ADDRESS).unwrap();
+        claim_eq!(player_data.state, PlayerState::Active, "Player should be reinstated");
+        claim_eq!(player_data.wins, 1, "Result should be recorded");
+        claim!(host.state().suspensions.get(&PLAYER_ADDRESS).is_none(), "Suspension should end");
+    }
+
+    /// Test that a suspended player is restored to its previous state, and
+    /// that `Strict` mode rejects suspending a suspended player.
+    #[concordium_test]
+    fn test_suspend_pending_player() {
+        // Set up the context.
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(100));
+        ctx.set_sender(ADMIN_ADDRESS);
+        let mut logger = TestLogger::init();
+
+        // Set up the state and host.
+        let mut state_builder = TestStateBuilder::new();
+        let mut state = initial_state(&mut state_builder);
+        add_player(&mut state, PLAYER_ADDRESS);
+        state.player_data.get_mut(&PLAYER_ADDRESS).unwrap().state = PlayerState::PendingApproval;
+        state.validation_mode = ValidationMode::Strict;
+        let mut host = TestHost::new(state, state_builder);
+
+        // Suspend the player that waits for approval.
+        let parameter_bytes = to_bytes(&SuspendPlayerParams {
+            player: PLAYER_ADDRESS,
+            until: Timestamp::from_timestamp_millis(1000),
+            reason: 7,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_suspend_player(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Results in rejection");
+
+        // Suspend the player again.
+        let result: ContractResult<()> = contract_suspend_player(&ctx, &mut host, &mut logger);
+        claim_eq!(
+            result,
+            Err(ContractError::InvalidStateTransition),
+            "Strict mode should reject suspending a suspended player"
+        );
+
+        // End the suspension.
+        let restored_state = host
+            .state_mut()
+            .lift_expired_suspension(&PLAYER_ADDRESS, Timestamp::from_timestamp_millis(1000));
+        claim_eq!(restored_state, Some(PlayerState::PendingApproval), "State should be restored");
+        claim_eq!(
+            host.state().get_player(&PLAYER_ADDRESS).map(|data| data.state),
+            Some(PlayerState::PendingApproval),
+            "Player should still wait for approval"
+        );
+    }
+
+    /// Test that a suspension must end in the future.
+    #[concordium_test]
+    fn test_suspend_player_invalid_expiry() {
+        // Set up the context.
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(100));
+        ctx.set_sender(ADMIN_ADDRESS);
+        let mut logger = TestLogger::init();
+
+        // Set up the parameter.
+        let parameter_bytes = to_bytes(&SuspendPlayerParams {
+            player: PLAYER_ADDRESS,
+            until: Timestamp::from_timestamp_millis(100),
+            reason: 1,
+        });
+        ctx.set_parameter(&parameter_bytes);
+
+        // Set up the state and host.
+        let mut state_builder = TestStateBuilder::new();
+        let mut state = initial_state(&mut state_builder);
+        add_player(&mut state, PLAYER_ADDRESS);
+        let mut host = TestHost::new(state, state_builder);
+
+        // Call the contract function.
+        let result: ContractResult<()> = contract_suspend_player(&ctx, &mut host, &mut logger);
+
+        // Check that invoke failed.
+        claim_eq!(result, Err(ContractError::InvalidExpiry), "Suspension should end in the future");
+    }
+
+    /// Test that strict mode rejects results for players that have not been
+    /// added, which lenient mode ignores.
+    #[concordium_test]
+    fn test_update_battle_result_strict() {
+        // Set up the context.
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+        ctx.set_sender(ADMIN_ADDRESS);
+        let mut logger = TestLogger::init();
+
+        // Set up the parameter.
+        let parameter_bytes = to_bytes(&UpdateBattleResultParams {
+            player: PLAYER_ADDRESS,
+            result: BattleResult::Win,
+            score: None,
+            opponent: None,
+        });
+        ctx.set_parameter(&parameter_bytes);
+
+        // Set up the state and host.
+        let mut state_builder = TestStateBuilder::new();
+        let state = initial_state(&mut state_builder);
+        let mut host = TestHost::new(state, state_builder);
+
+        // Report in lenient mode.
+        let result: ContractResult<()> = update_battle_result(&ctx, &mut host, &mut logger);
+        claim_eq!(result, Ok(()), "Lenient mode should ignore unknown players");
+
+        // Report in strict mode.
+        host.state_mut().validation_mode = ValidationMode::Strict;
+        let result: ContractResult<()> = update_battle_result(&ctx, &mut host, &mut logger);
+        claim_eq!(
+            result,
+            Err(ContractError::PlayerDoesNotExist),
+            "Strict mode should reject unknown players"
+        );
+    }
+
+    /// Test that strict mode only accepts valid state transitions.
+    #[concordium_test]
+    fn test_set_player_data_strict() {
+        // Set up the context.
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+        ctx.set_sender(ADMIN_ADDRESS);
+        let mut logger = TestLogger::init();
+
+        // Set up the state and host.
+        let mut state_builder = TestStateBuilder::new();
+        let mut state = initial_state(&mut state_builder);
+        state.validation_mode = ValidationMode::Strict;
+        add_player(&mut state, PLAYER_ADDRESS);
+        let mut host = TestHost::new(state, state_builder);
+
+        // Set the state the player already has.
+        let parameter_bytes = to_bytes(&(PLAYER_ADDRESS, PlayerState::Active));
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> =
+            contract_state_set_player_data(&ctx, &mut host, &mut logger);
+        claim_eq!(
+            result,
+            Err(ContractError::InvalidStateTransition),
+            "Same state should be rejected"
+        );
+
+        // Move an active player back to pending approval.
+        let parameter_bytes = to_bytes(&(PLAYER_ADDRESS, PlayerState::PendingApproval));
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> =
+            contract_state_set_player_data(&ctx, &mut host, &mut logger);
+        claim_eq!(
+            result,
+            Err(ContractError::InvalidStateTransition),
+            "Active players should not wait for approval"
+        );
+
+        // Suspend the player.
+        let parameter_bytes = to_bytes(&(PLAYER_ADDRESS, PlayerState::Suspended));
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> =
+            contract_state_set_player_data(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Results in rejection");
+    }
+
+    /// Test that an expired admin transfer cannot be accepted and that only
+    /// the proposed admin can accept.
+    #[concordium_test]
+    fn test_accept_admin_expired() {
+        // Set up the context.
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(1000));
+        ctx.set_sender(REPORTER_ADDRESS);
+        let mut logger = TestLogger::init();
+
+        // Set up the state and host.
+        let mut state_builder = TestStateBuilder::new();
+        let mut state = initial_state(&mut state_builder);
+        state.pending_admin = Some(PendingAdmin {
+            address: NEW_ADMIN_ADDRESS,
+            expiry: Some(Timestamp::from_timestamp_millis(1000)),
+        });
+        let mut host = TestHost::new(state, state_builder);
+
+        // Accept as another address.
+        let result: ContractResult<()> = contract_accept_admin(&ctx, &mut host, &mut logger);
+        claim_eq!(result, Err(ContractError::Unauthorized), "Only the proposed admin can accept");
+
+        // Accept after the expiry.
+        ctx.set_sender(NEW_ADMIN_ADDRESS);
+        let result: ContractResult<()> = contract_accept_admin(&ctx, &mut host, &mut logger);
+        claim_eq!(
+            result,
+            Err(ContractError::AdminTransferExpired),
+            "Expired transfer should not be accepted"
+        );
+        claim_eq!(host.state().admin, ADMIN_ADDRESS, "Admin should not change");
+    }
+
+    /// Test that the admin can cancel a proposed admin transfer.
+    #[concordium_test]
+    fn test_cancel_admin_transfer() {
+        // Set up the context.
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(ADMIN_ADDRESS);
+        let mut logger = TestLogger::init();
+
+        // Set up the state and host.
+        let mut state_builder = TestStateBuilder::new();
+        let mut state = initial_state(&mut state_builder);
+        state.pending_admin = Some(PendingAdmin {
+            address: NEW_ADMIN_ADDRESS,
+            expiry: None,
+        });
+        let mut host = TestHost::new(state, state_builder);
+
+        // Cancel the transfer.
+        let result: ContractResult<()> =
+            contract_cancel_admin_transfer(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Results in rejection");
+        claim_eq!(host.state().pending_admin, None, "Pending admin should be cleared");
+
+        // Accept the cancelled transfer.
+        ctx.set_sender(NEW_ADMIN_ADDRESS);
+        let result: ContractResult<()> = contract_accept_admin(&ctx, &mut host, &mut logger);
+        claim_eq!(result, Err(ContractError::NoPendingAdmin), "Transfer should be cancelled");
+    }
+
+    /// Test that a proposal runs once enough signers approve it.
+    #[concordium_test]
+    fn test_multisig_approvals() {
+        // Set up the state.
+        let mut state_builder = TestStateBuilder::new();
+        let mut state = initial_state(&mut state_builder);
+        state.multisig = Some(MultisigConfig {
+            signers: vec![ADMIN_ACCOUNT, NEW_ADMIN_ACCOUNT],
+            threshold: 2,
+        });
+        let now = Timestamp::from_timestamp_millis(0);
+        let expiry = Timestamp::from_timestamp_millis(1000);
+
+        // Submit the proposal as the first signer.
+        let signer = state.ensure_signer(&ADMIN_ADDRESS).unwrap();
+        let proposal_id = state.submit_proposal(signer, ProposalAction::SetPaused(true), expiry);
+        let result = state.approve_proposal(proposal_id, signer, now);
+        claim!(matches!(result, Ok((1, None))), "Proposal should wait for the second signer");
+
+        // Approve twice as the first signer.
+        let result = state.approve_proposal(proposal_id, signer, now);
+        claim!(
+            matches!(result, Err(ContractError::AlreadyApproved)),
+            "Signer should only approve once"
+        );
+
+        // Approve as the second signer.
+        let signer = state.ensure_signer(&NEW_ADMIN_ADDRESS).unwrap();
+        let result = state.approve_proposal(proposal_id, signer, now);
+        claim!(
+            matches!(result, Ok((2, Some(ProposalAction::SetPaused(true))))),
+            "Proposal should run with two approvals"
+        );
+        claim!(state.proposals.get(&proposal_id).unwrap().executed, "Proposal should be executed");
+
+        // Check that non-signers are rejected.
+        claim!(
+            matches!(state.ensure_signer(&PLAYER_ADDRESS), Err(ContractError::Unauthorized)),
+            "Only signers can approve"
+        );
+    }
+
+    /// Test that an expired proposal cannot be approved.
+    #[concordium_test]
+    fn test_multisig_proposal_expired() {
+        // Set up the state.
+        let mut state_builder = TestStateBuilder::new();
+        let mut state = initial_state(&mut state_builder);
+        state.multisig = Some(MultisigConfig {
+            signers: vec![ADMIN_ACCOUNT],
+            threshold: 1,
+        });
+        let expiry = Timestamp::from_timestamp_millis(1000);
+        let proposal_id =
+            state.submit_proposal(ADMIN_ACCOUNT, ProposalAction::SetPaused(true), expiry);
+
+        // Approve after the expiry.
+        let result = state.approve_proposal(proposal_id, ADMIN_ACCOUNT, expiry);
+
+        // Check that the approval failed.
+        claim!(
+            matches!(result, Err(ContractError::ProposalExpired)),
+            "Expired proposal should not be approved"
+        );
+    }
+
+    /// Test that the admin can no longer act alone once the multisig is
+    /// enabled.
+    #[concordium_test]
+    fn test_set_multisig() {
+        // Set up the context.
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(ADMIN_ADDRESS);
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+        let mut logger = TestLogger::init();
+
+        // Set up the state and host.
+        let mut state_builder = TestStateBuilder::new();
+        let state = initial_state(&mut state_builder);
+        let mut host = TestHost::new(state, state_builder);
+
+        // Enable a multisig with an unreachable threshold.
+        let parameter_bytes = to_bytes(&MultisigConfig {
+            signers: vec![ADMIN_ACCOUNT, NEW_ADMIN_ACCOUNT],
+            threshold: 3,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_set_multisig(&ctx, &mut host, &mut logger);
+        claim_eq!(result, Err(ContractError::InvalidMultisig), "Threshold should be reachable");
+
+        // Enable the multisig.
+        let parameter_bytes = to_bytes(&MultisigConfig {
+            signers: vec![ADMIN_ACCOUNT, NEW_ADMIN_ACCOUNT],
+            threshold: 2,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_set_multisig(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Results in rejection");
+
+        // Withdraw from the treasury as the admin.
+        let parameter_bytes = to_bytes(&WithdrawTreasuryParams {
+            to: ADMIN_ACCOUNT,
+            amount: Amount::zero(),
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_withdraw_treasury(&ctx, &mut host, &mut logger);
+        claim_eq!(
+            result,
+            Err(ContractError::MultisigRequired),
+            "Withdrawals should need the multisig"
+        );
+
+        // Grant a role as the admin.
+        let role_params = RoleParams {
+            address: REPORTER_ADDRESS,
+            role: Role::Reporter,
+        };
+        let parameter_bytes = to_bytes(&role_params);
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_grant_role(&ctx, &mut host, &mut logger);
+        claim_eq!(result, Err(ContractError::MultisigRequired), "Roles should need the multisig");
+
+        // Enable a game server as the admin.
+        let parameter_bytes = to_bytes(&SetGameServerParams {
+            public_key: GAME_SERVER_KEY,
+            enabled: true,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_set_game_server(&ctx, &mut host, &mut logger);
+        claim_eq!(
+            result,
+            Err(ContractError::MultisigRequired),
+            "Game servers should need the multisig"
+        );
+
+        // Pay out a prize as the admin.
+        let parameter_bytes = to_bytes(&PrizePayout {
+            key: PrizePoolKey {
+                pool: String::from("season-1"),
+                token_contract: TOKEN_CONTRACT,
+                token_id: TokenIdVec(vec![1]),
+            },
+            amount: TokenAmountU64(0),
+            to: Receiver::Account(ADMIN_ACCOUNT),
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_payout_prize(&ctx, &mut host, &mut logger);
+        claim_eq!(result, Err(ContractError::MultisigRequired), "Prizes should need the multisig");
+
+        // Pause the contract as the admin.
+        let parameter_bytes = to_bytes(&SetPausedParams {
+            paused: true,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_set_paused(&ctx, &mut host, &mut logger);
+        claim_eq!(result, Err(ContractError::MultisigRequired), "Pausing should need the multisig");
+        claim!(!host.state().paused, "Contract should not be paused");
+
+        // Grant the role through a proposal approved by both signers.
+        let parameter_bytes = to_bytes(&SubmitProposalParams {
+            action: ProposalAction::GrantRole(role_params),
+            expiry: Timestamp::from_timestamp_millis(1_000),
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let proposal_id = contract_submit_proposal(&ctx, &mut host, &mut logger).unwrap();
+        claim!(!host.state().has_role(&REPORTER_ADDRESS, Role::Reporter), "Role needs approval");
+        let parameter_bytes = to_bytes(&proposal_id);
+        ctx.set_parameter(&parameter_bytes);
+        ctx.set_sender(NEW_ADMIN_ADDRESS);
+        let result: ContractResult<()> = contract_approve_proposal(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Results in rejection");
+        claim!(host.state().has_role(&REPORTER_ADDRESS, Role::Reporter), "Role should be granted");
+    }
+
+    /// Test scheduling, viewing and cancelling an upgrade.
+    #[concordium_test]
+    fn test_schedule_upgrade() {
+        // Set up the context.
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(ADMIN_ADDRESS);
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(1_000));
+        let mut logger = TestLogger::init();
+
+        // Set up the state and host.
+        let mut state_builder = TestStateBuilder::new();
+        let state = initial_state(&mut state_builder);
+        let mut host = TestHost::new(state, state_builder);
+
+        // Schedule an upgrade before the delay has passed.
+        let module = ModuleReference::from([1u8; 32]);
+        let execute_after = Timestamp::from_timestamp_millis(1_000)
+            .checked_add(UPGRADE_DELAY)
+            .unwrap_abort();
+        let parameter_bytes = to_bytes(&UpgradeParams {
+            module,
+            migrate: None,
+            execute_after: Timestamp::from_timestamp_millis(execute_after.timestamp_millis() - 1),
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> =
+            contract_schedule_upgrade(&ctx, &mut host, &mut logger);
+        claim_eq!(result, Err(ContractError::UpgradeTooEarly), "Delay should be enforced");
+
+        // Schedule the upgrade.
+        let parameter_bytes = to_bytes(&UpgradeParams {
+            module,
+            migrate: None,
+            execute_after,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> =
+            contract_schedule_upgrade(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Results in rejection");
+        claim!(
+            logger.logs.contains(&to_bytes(&VersusEvent::UpgradeScheduled(
+                UpgradeScheduledEvent {
+                    module,
+                    execute_after,
+                }
+            ))),
+            "Missing event for the scheduled upgrade"
+        );
+
+        // Check the pending upgrade.
+        let pending_upgrade = contract_get_pending_upgrade(&ctx, &host)
+            .expect_report("Failed getting the pending upgrade");
+        claim_eq!(pending_upgrade.map(|upgrade| upgrade.module), Some(module));
+
+        // Cancel the upgrade.
+        let result: ContractResult<()> = contract_cancel_upgrade(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Results in rejection");
+        claim!(host.state().pending_upgrade.is_none(), "Upgrade should be cancelled");
+
+        // Cancel again.
+        let result: ContractResult<()> = contract_cancel_upgrade(&ctx, &mut host, &mut logger);
+        claim_eq!(result, Err(ContractError::NoPendingUpgrade), "No upgrade should be pending");
+    }
+
+    /// Test that only the admin can schedule an upgrade.
+    #[concordium_test]
+    fn test_schedule_upgrade_not_authorized() {
+        // Set up the context.
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(NEW_ADMIN_ADDRESS);
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+        let mut logger = TestLogger::init();
+
+        // Set up the state and host.
+        let mut state_builder = TestStateBuilder::new();
+        let state = initial_state(&mut state_builder);
+        let mut host = TestHost::new(state, state_builder);
+
+        let parameter_bytes = to_bytes(&UpgradeParams {
+            module: ModuleReference::from([1u8; 32]),
+            migrate: None,
+            execute_after: Timestamp::from_timestamp_millis(u64::MAX),
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> =
+            contract_schedule_upgrade(&ctx, &mut host, &mut logger);
+        claim_eq!(result, Err(ContractError::Unauthorized), "Only the admin can schedule");
+        claim!(host.state().pending_upgrade.is_none(), "No upgrade should be pending");
+    }
+
+    /// Test that the state of version 0 is migrated to the current layout,
+    /// and that the migration is not applied twice.
+    #[concordium_test]
+    fn test_migrate_v0() {
+        // Set up the state of version 0.
+        let mut state_builder = TestStateBuilder::new();
+        let mut player_data = state_builder.new_map();
+        player_data.insert(PLAYER_ADDRESS, PlayerDataV0 {
+            state: PlayerState::Active,
+            wins: 3,
+            losses: 1,
+        });
+        player_data.insert(OPPONENT_ADDRESS, PlayerDataV0 {
+            state: PlayerState::Suspended,
+            wins: 0,
+            losses: 2,
+        });
+        let mut implementors = state_builder.new_map();
+        implementors.insert(StandardIdentifierOwned::new_unchecked("CIS-2".to_string()), vec![
+            TOKEN_CONTRACT,
+        ]);
+        let legacy = StateV0 {
+            admin: ADMIN_ADDRESS,
+            player_data,
+            paused: true,
+            implementors,
+        };
+        let mut state_api = state_builder.into_inner();
+        state_api.write_root(&legacy);
+        let mut state_builder = StateBuilder::open(state_api.clone());
+
+        // Migrate the state.
+        claim_eq!(read_state_header(&state_api), Ok((0, ADMIN_ADDRESS)));
+        let now = Timestamp::from_timestamp_millis(1_000);
+        let result = migrate_state(&mut state_api, &mut state_builder, 0, now);
+        claim!(result.is_ok(), "Results in rejection");
+
+        // Check the migrated state.
+        claim_eq!(read_state_header(&state_api), Ok((STATE_VERSION, ADMIN_ADDRESS)));
+        let state: State<TestStateApi> =
+            state_api.read_root().expect_report("Failed reading the migrated state");
+        claim_eq!(state.version, StateVersion(STATE_VERSION));
+        claim!(state.paused, "Contract should stay paused");
+        claim_eq!(state.player_count, 2);
+        claim!(state.has_role(&ADMIN_ADDRESS, Role::Reporter), "Admin should be a reporter");
+        let player_data = state.player_data.get(&PLAYER_ADDRESS).expect_report("Player missing");
+        claim_eq!(player_data.state, PlayerState::Active);
+        claim_eq!(player_data.wins, 3);
+        claim_eq!(player_data.losses, 1);
+        claim_eq!(player_data.rating, DEFAULT_RATING);
+        claim_eq!(player_data.registered_at, now);
+        let opponent_data =
+            state.player_data.get(&OPPONENT_ADDRESS).expect_report("Opponent missing");
+        claim_eq!(opponent_data.state, PlayerState::Suspended);
+        claim_eq!(opponent_data.losses, 2);
+        let implementors = state
+            .implementors
+            .get(&StandardIdentifierOwned::new_unchecked("CIS-2".to_string()))
+            .map(|implementors| (*implementors).clone());
+        claim_eq!(implementors, Some(vec![TOKEN_CONTRACT]));
+        claim_eq!(state.leaderboard.get().len(), 2);
+
+        // Migrate again.
+        let result = migrate_state(&mut state_api, &mut state_builder, STATE_VERSION, now);
+        claim_eq!(result, Err(ContractError::StateAlreadyMigrated), "Should not migrate twice");
+    }
+
+    /// Test that the migration rejects state layouts it does not know and
+    /// steps applied to the wrong layout.
+    #[concordium_test]
+    fn test_migrate_wrong_version() {
+        // Set up a state of a newer version.
+        let mut state_builder = TestStateBuilder::new();
+        let state = initial_state(&mut state_builder);
+        let mut state_api = state_builder.into_inner();
+        state_api.write_root(&(StateVersion(STATE_VERSION + 1), ADMIN_ADDRESS));
+        let mut state_builder = StateBuilder::open(state_api.clone());
+        let now = Timestamp::from_timestamp_millis(0);
+
+        let (version, _) = read_state_header(&state_api).expect_report("Failed reading header");
+        let result = migrate_state(&mut state_api, &mut state_builder, version, now);
+        claim_eq!(result, Err(ContractError::UnknownStateVersion), "Version should be unknown");
+
+        // Apply the first step to the current layout.
+        state_api.write_root(&state);
+        let result = migrate_v0_to_v1(&mut state_api, &mut state_builder, now);
+        claim_eq!(result, Err(ContractError::ParseParams), "Layout should not be version 0");
+    }
+
+    /// Test the supports query with and without implementors.
+    #[concordium_test]
+    fn test_supports() {
+        // Set up the context.
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(ADMIN_ADDRESS);
+        let mut logger = TestLogger::init();
+
+        // Set up the state and host.
+        let mut state_builder = TestStateBuilder::new();
+        let state = initial_state(&mut state_builder);
+        let mut host = TestHost::new(state, state_builder);
+
+        // Set an implementor of CIS-2.
+        let cis2 = StandardIdentifierOwned::new_unchecked("CIS-2".to_string());
+        let parameter_bytes = to_bytes(&SetImplementorsParams {
+            id: cis2.clone(),
+            implementors: vec![TOKEN_CONTRACT],
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_set_implementors(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Results in rejection");
+        claim_eq!(
+            logger.logs[0],
+            to_bytes(&VersusEvent::Implementors(ImplementorsEvent {
+                id: cis2.clone(),
+                implementors: vec![TOKEN_CONTRACT],
+            })),
+            "Incorrect event emitted"
+        );
+
+        // Query the supported standards.
+        let parameter_bytes = to_bytes(&SupportsQueryParams {
+            queries: vec![
+                CIS0_STANDARD_IDENTIFIER.to_owned(),
+                cis2,
+                StandardIdentifierOwned::new_unchecked("CIS-3".to_string()),
+            ],
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let response = contract_supports(&ctx, &host).expect_report("Failed querying support");
+        let expected = SupportsQueryResponse::from(vec![
+            SupportResult::Support,
+            SupportResult::SupportBy(vec![TOKEN_CONTRACT]),
+            SupportResult::NoSupport,
+        ]);
+        claim_eq!(to_bytes(&response), to_bytes(&expected), "Unexpected support results");
+    }
+
+    /// Test that only admins can set implementors.
+    #[concordium_test]
+    fn test_set_implementors_not_authorized() {
+        // Set up the context.
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(NEW_ADMIN_ADDRESS);
+        let parameter_bytes = to_bytes(&SetImplementorsParams {
+            id: StandardIdentifierOwned::new_unchecked("CIS-2".to_string()),
+            implementors: vec![TOKEN_CONTRACT],
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let mut logger = TestLogger::init();
+
+        // Set up the state and host.
+        let mut state_builder = TestStateBuilder::new();
+        let state = initial_state(&mut state_builder);
+        let mut host = TestHost::new(state, state_builder);
+
+        let result: ContractResult<()> = contract_set_implementors(&ctx, &mut host, &mut logger);
+        claim_eq!(result, Err(ContractError::Unauthorized), "Only admins can set implementors");
+    }
+
+    /// Test that a result signed by a game server can be submitted by anyone,
+    /// but only once.
+    #[concordium_test]
+    fn test_submit_signed_result() {
+        // Set up the context.
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(ADMIN_ADDRESS);
+        ctx.set_self_address(ContractAddress {
+            index: 1,
+            subindex: 0,
+        });
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+        let mut logger = TestLogger::init();
+        let crypto_primitives = TestCryptoPrimitives::new();
+
+        // Set up the state and host.
+        let mut state_builder = TestStateBuilder::new();
+        let mut state = initial_state(&mut state_builder);
+        add_player(&mut state, PLAYER_ADDRESS);
+        add_player(&mut state, OPPONENT_ADDRESS);
+        let mut host = TestHost::new(state, state_builder);
+
+        // Register the game server.
+        let parameter_bytes = to_bytes(&SetGameServerParams {
+            public_key: GAME_SERVER_KEY,
+            enabled: true,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_set_game_server(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Results in rejection");
+
+        // Submit the signed result as a player.
+        ctx.set_sender(OPPONENT_ADDRESS);
+        let parameter_bytes = to_bytes(&SubmitSignedResultParams {
+            server: GAME_SERVER_KEY,
+            signature: GAME_SERVER_SIGNATURE,
+            message: signed_result_message(),
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> =
+            contract_submit_signed_result(&ctx, &mut host, &mut logger, &crypto_primitives);
+        claim!(result.is_ok(), "Results in rejection");
+
+        // Check the battle and the nonce of the game server.
+        claim_eq!(host.state().player_data.get(&PLAYER_ADDRESS).map(|data| data.wins), Some(1));
+        claim!(host.state().battles.get(&1).is_some(), "Battle should be recorded");
+        claim_eq!(
+            host.state().game_servers.get(&GAME_SERVER_KEY).map(|server| *server),
+            Some(GameServer {
+                enabled: true,
+                nonce: 1,
+            })
+        );
+        claim!(
+            logger.logs.contains(&to_bytes(&VersusEvent::SignedResult(SignedResultEvent {
+                server: GAME_SERVER_KEY,
+                nonce: 0,
+                battle_id: 1,
+            }))),
+            "Missing event for the signed result"
+        );
+
+        // Replay the signed result.
+        let result: ContractResult<()> =
+            contract_submit_signed_result(&ctx, &mut host, &mut logger, &crypto_primitives);
+        claim_eq!(result, Err(ContractError::NonceMismatch), "Signature should not be replayed");
+    }
+
+    /// Test that expired, tampered and unregistered signed results are
+    /// rejected.
+    #[concordium_test]
+    fn test_submit_signed_result_invalid() {
+        // Set up the context.
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_self_address(ContractAddress {
+            index: 1,
+            subindex: 0,
+        });
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+        let mut logger = TestLogger::init();
+        let crypto_primitives = TestCryptoPrimitives::new();
 
-    const ADMIN_ACCOUNT: AccountAddress = AccountAddress([2u8; 32]);
-    const ADMIN_ADDRESS: Address = Address::Account(ADMIN_ACCOUNT);
-    const NEW_ADMIN_ACCOUNT: AccountAddress = AccountAddress([3u8; 32]);
-    const NEW_ADMIN_ADDRESS: Address = Address::Account(NEW_ADMIN_ACCOUNT);
+        // Set up the state and host.
+        let mut state_builder = TestStateBuilder::new();
+        let mut state = initial_state(&mut state_builder);
+        add_player(&mut state, PLAYER_ADDRESS);
+        add_player(&mut state, OPPONENT_ADDRESS);
+        let mut host = TestHost::new(state, state_builder);
+
+        // Submit before the game server is registered.
+        let parameter_bytes = to_bytes(&SubmitSignedResultParams {
+            server: GAME_SERVER_KEY,
+            signature: GAME_SERVER_SIGNATURE,
+            message: signed_result_message(),
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> =
+            contract_submit_signed_result(&ctx, &mut host, &mut logger, &crypto_primitives);
+        claim_eq!(result, Err(ContractError::UnknownGameServer), "Server should be unknown");
+
+        host.state_mut().game_servers.insert(GAME_SERVER_KEY, GameServer {
+            enabled: true,
+            nonce: 0,
+        });
+
+        // Submit a tampered result.
+        let mut message = signed_result_message();
+        message.report.winner = OPPONENT_ADDRESS;
+        message.report.loser = PLAYER_ADDRESS;
+        let parameter_bytes = to_bytes(&SubmitSignedResultParams {
+            server: GAME_SERVER_KEY,
+            signature: GAME_SERVER_SIGNATURE,
+            message,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> =
+            contract_submit_signed_result(&ctx, &mut host, &mut logger, &crypto_primitives);
+        claim_eq!(result, Err(ContractError::WrongSignature), "Signature should not match");
 
+        // Submit the result after it has expired.
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(10_000));
+        let parameter_bytes = to_bytes(&SubmitSignedResultParams {
+            server: GAME_SERVER_KEY,
+            signature: GAME_SERVER_SIGNATURE,
+            message: signed_result_message(),
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> =
+            contract_submit_signed_result(&ctx, &mut host, &mut logger, &crypto_primitives);
+        claim_eq!(result, Err(ContractError::SignatureExpired), "Message should be expired");
+    }
 
-    /// Test admin can update to a new admin address.
+    /// Test that a proposed result changes the players' data only once the
+    /// losing side confirms it.
     #[concordium_test]
-    fn test_update_admin() {
-        // Set up the context
+    fn test_confirm_result() {
+        // Set up the context.
         let mut ctx = TestReceiveContext::empty();
         ctx.set_sender(ADMIN_ADDRESS);
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
         let mut logger = TestLogger::init();
 
-        // Set up the parameter.
-        let parameter_bytes = to_bytes(&[NEW_ADMIN_ADDRESS]);
-        ctx.set_parameter(&parameter_bytes);
-
         // Set up the state and host.
         let mut state_builder = TestStateBuilder::new();
-        let state = initial_state(&mut state_builder);
+        let mut state = initial_state(&mut state_builder);
+        add_player(&mut state, PLAYER_ADDRESS);
+        add_player(&mut state, OPPONENT_ADDRESS);
         let mut host = TestHost::new(state, state_builder);
 
-        // Check the admin state.
-        claim_eq!(host.state().admin, ADMIN_ADDRESS, "Admin should be the old admin address");
+        // Propose the result.
+        let parameter_bytes = to_bytes(&match_report(1));
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_propose_result(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Results in rejection");
+        claim_eq!(host.state().player_data.get(&PLAYER_ADDRESS).map(|data| data.wins), Some(0));
 
-        // Call the contract function.
-        let result: ContractResult<()> = contract_update_admin(&ctx, &mut host, &mut logger);
+        // Propose the result again.
+        let result: ContractResult<()> = contract_propose_result(&ctx, &mut host, &mut logger);
+        claim_eq!(result, Err(ContractError::ResultAlreadyProposed), "Result is pending");
 
-        // Check the result.
-        claim!(result.is_ok(), "Results in rejection");
+        // Report the battle directly while its result is pending.
+        let result: ContractResult<()> = contract_report_match(&ctx, &mut host, &mut logger);
+        claim_eq!(result, Err(ContractError::ResultAlreadyProposed), "Result is pending");
 
-        // Check the admin state.
-        claim_eq!(host.state().admin, NEW_ADMIN_ADDRESS, "Admin should be the new admin address");
+        // Confirm the result as the winner.
+        let parameter_bytes = to_bytes(&1u64);
+        ctx.set_parameter(&parameter_bytes);
+        ctx.set_sender(PLAYER_ADDRESS);
+        let result: ContractResult<()> = contract_confirm_result(&ctx, &mut host, &mut logger);
+        claim_eq!(result, Err(ContractError::Unauthorized), "Only the loser can confirm");
 
-        // Check the logs
-        claim_eq!(logger.logs.len(), 1, "Exactly one event should be logged");
+        // Confirm the result as the loser.
+        ctx.set_sender(OPPONENT_ADDRESS);
+        let result: ContractResult<()> = contract_confirm_result(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Results in rejection");
 
-        // Check the event
+        // Check the state.
+        claim_eq!(host.state().player_data.get(&PLAYER_ADDRESS).map(|data| data.wins), Some(1));
+        claim_eq!(host.state().player_data.get(&OPPONENT_ADDRESS).map(|data| data.losses), Some(1));
+        claim!(host.state().battles.get(&1).is_some(), "Battle should be recorded");
+        claim!(host.state().pending_results.get(&1).is_none(), "Result should not be pending");
+
+        // Check the events.
         claim!(
-            logger.logs.contains(&to_bytes(&WccdEvent::NewAdmin(NewAdminEvent {
-                new_admin: NEW_ADMIN_ADDRESS,
-            }))),
-            "Missing event for the new admin"
+            logger.logs.contains(&to_bytes(&VersusEvent::ResultConfirmed(
+                ResultConfirmedEvent {
+                    battle_id: 1,
+                    player: OPPONENT_ADDRESS,
+                }
+            ))),
+            "Missing event for the confirmation"
+        );
+        claim!(
+            logger.logs.contains(&to_bytes(&VersusEvent::ResultFinalized(
+                ResultFinalizedEvent {
+                    battle_id: 1,
+                }
+            ))),
+            "Missing event for the finalization"
         );
     }
 
-    /// Test that only the current admin can update the admin address.
+    /// Test that a proposed result is finalized after the dispute window,
+    /// unless it has been disputed.
     #[concordium_test]
-    fn test_update_admin_not_authorized() {
+    fn test_dispute_and_finalize_result() {
         // Set up the context.
         let mut ctx = TestReceiveContext::empty();
-        // NEW_ADMIN is not the current admin but tries to update the admin variable to
-        // its own address.
-        ctx.set_sender(NEW_ADMIN_ADDRESS);
+        ctx.set_sender(ADMIN_ADDRESS);
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
         let mut logger = TestLogger::init();
 
-        // Set up the parameter.
-        let parameter_bytes = to_bytes(&[NEW_ADMIN_ADDRESS]);
+        // Set up the state and host.
+        let mut state_builder = TestStateBuilder::new();
+        let mut state = initial_state(&mut state_builder);
+        add_player(&mut state, PLAYER_ADDRESS);
+        add_player(&mut state, OPPONENT_ADDRESS);
+        let mut host = TestHost::new(state, state_builder);
+
+        // Propose two results.
+        for battle_id in 1..=2 {
+            let parameter_bytes = to_bytes(&match_report(battle_id));
+            ctx.set_parameter(&parameter_bytes);
+            let result: ContractResult<()> =
+                contract_propose_result(&ctx, &mut host, &mut logger);
+            claim!(result.is_ok(), "Results in rejection");
+        }
+
+        // Dispute the first result as the loser.
+        let parameter_bytes = to_bytes(&DisputeParams {
+            battle_id: 1,
+            evidence: [1u8; 32],
+        });
+        ctx.set_parameter(&parameter_bytes);
+        ctx.set_sender(OPPONENT_ADDRESS);
+        host.state_mut().paused = true;
+        let result: ContractResult<()> = contract_dispute_result(&ctx, &mut host, &mut logger);
+        claim_eq!(result, Err(ContractError::ContractPaused), "Contract should be paused");
+        host.state_mut().paused = false;
+        let result: ContractResult<()> = contract_dispute_result(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Results in rejection");
+
+        // Submit evidence while the contract is paused.
+        host.state_mut().paused = true;
+        let result: ContractResult<()> = contract_submit_evidence(&ctx, &mut host, &mut logger);
+        claim_eq!(result, Err(ContractError::ContractPaused), "Contract should be paused");
+        host.state_mut().paused = false;
+
+        // Finalize the second result before the deadline.
+        let parameter_bytes = to_bytes(&2u64);
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_finalize_result(&ctx, &mut host, &mut logger);
+        claim_eq!(result, Err(ContractError::DisputeWindowOpen), "Window should be open");
+
+        // Dispute the second result after the deadline.
+        let deadline = Timestamp::from_timestamp_millis(0)
+            .checked_add(DEFAULT_DISPUTE_WINDOW)
+            .unwrap_abort();
+        ctx.set_metadata_slot_time(deadline);
+        let parameter_bytes = to_bytes(&DisputeParams {
+            battle_id: 2,
+            evidence: [1u8; 32],
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_dispute_result(&ctx, &mut host, &mut logger);
+        claim_eq!(result, Err(ContractError::DisputeWindowClosed), "Window should be closed");
+
+        // Finalize the second result.
+        let parameter_bytes = to_bytes(&2u64);
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_finalize_result(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Results in rejection");
+        claim_eq!(host.state().player_data.get(&PLAYER_ADDRESS).map(|data| data.wins), Some(1));
+
+        // Finalize the disputed result.
+        let parameter_bytes = to_bytes(&1u64);
         ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_finalize_result(&ctx, &mut host, &mut logger);
+        claim_eq!(result, Err(ContractError::InvalidResultStatus), "Result is disputed");
+        claim!(host.state().battles.get(&1).is_none(), "Battle should not be recorded");
+    }
+
+    /// Test that overturning a reported battle rolls it back and records the
+    /// reversed result, also in the season in progress.
+    #[concordium_test]
+    fn test_overturn_reported_battle() {
+        // Set up the context.
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(ADMIN_ADDRESS);
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+        let mut logger = TestLogger::init();
 
         // Set up the state and host.
         let mut state_builder = TestStateBuilder::new();
-        let state = initial_state(&mut state_builder);
+        let mut state = initial_state(&mut state_builder);
+        add_player(&mut state, PLAYER_ADDRESS);
+        add_player(&mut state, OPPONENT_ADDRESS);
+        let season = state.start_season(Timestamp::from_timestamp_millis(0));
         let mut host = TestHost::new(state, state_builder);
 
-        // Check the admin state.
-        claim_eq!(host.state().admin, ADMIN_ADDRESS, "Admin should be the old admin address");
+        // Report the battle.
+        let parameter_bytes = to_bytes(&match_report(1));
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_report_match(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Results in rejection");
+        let winner_rating =
+            host.state().player_data.get(&PLAYER_ADDRESS).map(|data| data.rating).unwrap();
+        let loser_rating =
+            host.state().player_data.get(&OPPONENT_ADDRESS).map(|data| data.rating).unwrap();
 
-        // Call the contract function.
-        let result: ContractResult<()> = contract_update_admin(&ctx, &mut host, &mut logger);
+        // Dispute the result as the loser and submit evidence as the winner.
+        ctx.set_sender(OPPONENT_ADDRESS);
+        let parameter_bytes = to_bytes(&DisputeParams {
+            battle_id: 1,
+            evidence: [1u8; 32],
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_dispute_result(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Results in rejection");
+        ctx.set_sender(PLAYER_ADDRESS);
+        let parameter_bytes = to_bytes(&DisputeParams {
+            battle_id: 1,
+            evidence: [2u8; 32],
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_submit_evidence(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Results in rejection");
 
-        // Check that invoke failed.
+        // Suspend the winner, then overturn the result.
+        host.state_mut().player_data.get_mut(&PLAYER_ADDRESS).unwrap().state =
+            PlayerState::Suspended;
+        ctx.set_sender(ADMIN_ADDRESS);
+        let parameter_bytes = to_bytes(&ResolveDisputeParams {
+            battle_id: 1,
+            decision: DisputeDecision::Overturn,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_resolve_dispute(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Results in rejection");
+
+        // Check the players.
+        let player_data = host.state().get_player(&PLAYER_ADDRESS).unwrap();
+        claim_eq!(player_data.state, PlayerState::Suspended);
+        claim_eq!((player_data.wins, player_data.losses), (0, 1));
+        claim_eq!(player_data.rating, loser_rating);
+        claim_eq!(player_data.current_streak, -1);
+        claim_eq!(player_data.best_win_streak, 0);
+        let opponent_data = host.state().get_player(&OPPONENT_ADDRESS).unwrap();
+        claim_eq!((opponent_data.wins, opponent_data.losses), (1, 0));
+        claim_eq!(opponent_data.rating, winner_rating);
+        claim_eq!(opponent_data.worst_loss_streak, 0);
+        let season_data = host.state().get_season_player(season, &PLAYER_ADDRESS).unwrap();
+        claim_eq!((season_data.wins, season_data.losses), (0, 1));
+        claim_eq!(season_data.rating, loser_rating);
+        let season_data = host.state().get_season_player(season, &OPPONENT_ADDRESS).unwrap();
+        claim_eq!((season_data.wins, season_data.losses), (1, 0));
+
+        // Check the battle and the dispute.
+        let battle = host.state().battles.get(&1).map(|battle| (*battle).clone()).unwrap();
+        claim_eq!((battle.winner, battle.loser), (OPPONENT_ADDRESS, PLAYER_ADDRESS));
+        claim!(host.state().battle_changes.get(&1).is_none(), "No change should be kept");
+        let dispute = host.state().disputes.get(&1).map(|dispute| (*dispute).clone());
         claim_eq!(
-            result,
-            Err(ContractError::Unauthorized),
-            "Update admin should fail because not the current admin tries to update"
+            dispute,
+            Some(Dispute {
+                winner: PLAYER_ADDRESS,
+                loser: OPPONENT_ADDRESS,
+                opened_by: OPPONENT_ADDRESS,
+                winner_evidence: Some([2u8; 32]),
+                loser_evidence: Some([1u8; 32]),
+                status: DisputeStatus::Overturned,
+            })
+        );
+        claim!(
+            logger.logs.contains(&to_bytes(&VersusEvent::DisputeResolved(
+                DisputeResolvedEvent {
+                    battle_id: 1,
+                    arbitrator: ADMIN_ADDRESS,
+                    decision: DisputeDecision::Overturn,
+                }
+            ))),
+            "Missing event for the decision"
         );
 
-        // Check the admin state.
-        claim_eq!(host.state().admin, ADMIN_ADDRESS, "Admin should be still the old admin address");
+        // Resolve the dispute again.
+        let result: ContractResult<()> = contract_resolve_dispute(&ctx, &mut host, &mut logger);
+        claim_eq!(result, Err(ContractError::InvalidDisputeStatus), "Dispute is resolved");
     }
 
-    /// Test pausing the contract.
+    /// Test that voiding a reported battle restores the players' data, but
+    /// keeps the standings of a season that has ended.
     #[concordium_test]
-    fn test_pause() {
+    fn test_void_reported_battle() {
         // Set up the context.
         let mut ctx = TestReceiveContext::empty();
         ctx.set_sender(ADMIN_ADDRESS);
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+        let mut logger = TestLogger::init();
 
-        // Set up the parameter to pause the contract.
-        let parameter_bytes = to_bytes(&true);
+        // Set up the state and host.
+        let mut state_builder = TestStateBuilder::new();
+        let mut state = initial_state(&mut state_builder);
+        add_player(&mut state, PLAYER_ADDRESS);
+        add_player(&mut state, OPPONENT_ADDRESS);
+        let season = state.start_season(Timestamp::from_timestamp_millis(0));
+        let mut host = TestHost::new(state, state_builder);
+
+        // Report the battle.
+        let parameter_bytes = to_bytes(&match_report(1));
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_report_match(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Results in rejection");
+
+        // Dispute the result as the winner.
+        ctx.set_sender(PLAYER_ADDRESS);
+        let parameter_bytes = to_bytes(&DisputeParams {
+            battle_id: 1,
+            evidence: [1u8; 32],
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_dispute_result(&ctx, &mut host, &mut logger);
+        claim_eq!(result, Err(ContractError::Unauthorized), "Only the loser can dispute");
+
+        // Dispute the result as the loser, twice.
+        ctx.set_sender(OPPONENT_ADDRESS);
+        let result: ContractResult<()> = contract_dispute_result(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Results in rejection");
+        let result: ContractResult<()> = contract_dispute_result(&ctx, &mut host, &mut logger);
+        claim_eq!(result, Err(ContractError::AlreadyDisputed), "Result is disputed");
+
+        // Void the result as a player.
+        let parameter_bytes = to_bytes(&ResolveDisputeParams {
+            battle_id: 1,
+            decision: DisputeDecision::Void,
+        });
         ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_resolve_dispute(&ctx, &mut host, &mut logger);
+        claim_eq!(result, Err(ContractError::Unauthorized), "Only arbitrators can resolve");
+
+        // End the season, then void the result as an arbitrator.
+        host.state_mut().seasons.get_mut(&season).unwrap().ended_at =
+            Some(Timestamp::from_timestamp_millis(0));
+        ctx.set_sender(ADMIN_ADDRESS);
+        let result: ContractResult<()> = contract_resolve_dispute(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Results in rejection");
+
+        // Check that the battle is voided and the players are as before the
+        // battle.
+        let battle = host.state().battles.get(&1).map(|battle| (*battle).clone()).unwrap();
+        claim!(battle.voided, "Battle should be voided");
+        for player in [PLAYER_ADDRESS, OPPONENT_ADDRESS] {
+            let player_data = host.state().get_player(&player).unwrap();
+            claim_eq!(player_data.games_played(), 0);
+            claim_eq!(player_data.rating, DEFAULT_RATING);
+            claim_eq!(player_data.current_streak, 0);
+            claim_eq!((player_data.best_win_streak, player_data.worst_loss_streak), (0, 0));
+            claim_eq!(player_data.last_played, None);
+            let season_data = host.state().get_season_player(season, &player).unwrap();
+            claim_eq!(season_data.games_played(), 1, "Ended season should be kept");
+        }
+    }
+
+    /// Test that the change of a reported battle is only pruned once the
+    /// battle can no longer be disputed.
+    #[concordium_test]
+    fn test_prune_battle_changes() {
+        // Set up the context.
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(ADMIN_ADDRESS);
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+        let mut logger = TestLogger::init();
 
         // Set up the state and host.
         let mut state_builder = TestStateBuilder::new();
-        let state = initial_state(&mut state_builder);
+        let mut state = initial_state(&mut state_builder);
+        add_player(&mut state, PLAYER_ADDRESS);
+        add_player(&mut state, OPPONENT_ADDRESS);
         let mut host = TestHost::new(state, state_builder);
 
-        // Call the contract function.
-        let result: ContractResult<()> = contract_set_paused(&ctx, &mut host);
+        // Report the battle.
+        let parameter_bytes = to_bytes(&match_report(1));
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_report_match(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Results in rejection");
 
-        // Check the result.
+        // Prune while the battle can still be disputed.
+        let parameter_bytes = to_bytes(&vec![1u64, 2u64]);
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_prune_battle_changes(&ctx, &mut host);
         claim!(result.is_ok(), "Results in rejection");
+        claim!(host.state().battle_changes.get(&1).is_some(), "Change should be kept");
 
-        // Check contract is paused.
-        claim_eq!(host.state().paused, true, "Smart contract should be paused");
+        // Prune after the dispute window.
+        let deadline = Timestamp::from_timestamp_millis(0)
+            .checked_add(DEFAULT_DISPUTE_WINDOW)
+            .unwrap_abort();
+        ctx.set_metadata_slot_time(deadline);
+        let result: ContractResult<()> = contract_prune_battle_changes(&ctx, &mut host);
+        claim!(result.is_ok(), "Results in rejection");
+        claim!(host.state().battle_changes.get(&1).is_none(), "Change should be pruned");
+
+        // Dispute the battle after a longer dispute window is set.
+        host.state_mut().dispute_window = Duration::from_millis(365 * 24 * 60 * 60 * 1000);
+        ctx.set_sender(OPPONENT_ADDRESS);
+        let parameter_bytes = to_bytes(&DisputeParams {
+            battle_id: 1,
+            evidence: [1u8; 32],
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_dispute_result(&ctx, &mut host, &mut logger);
+        claim_eq!(result, Err(ContractError::DisputeWindowClosed), "Change has been pruned");
     }
 
-    /// Test unpausing the contract.
+
+    /// Test that a voided proposed result keeps its battle id, so that the
+    /// result cannot be proposed again without a way to dispute it.
     #[concordium_test]
-    fn test_unpause() {
+    fn test_void_pending_result() {
         // Set up the context.
         let mut ctx = TestReceiveContext::empty();
         ctx.set_sender(ADMIN_ADDRESS);
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+        let mut logger = TestLogger::init();
 
-        // Set up the parameter to pause the contract.
-        let parameter_bytes = to_bytes(&true);
+        // Set up the state and host.
+        let mut state_builder = TestStateBuilder::new();
+        let mut state = initial_state(&mut state_builder);
+        add_player(&mut state, PLAYER_ADDRESS);
+        add_player(&mut state, OPPONENT_ADDRESS);
+        let mut host = TestHost::new(state, state_builder);
+
+        // Propose the result and dispute it as the loser.
+        let report_bytes = to_bytes(&match_report(1));
+        ctx.set_parameter(&report_bytes);
+        let result: ContractResult<()> = contract_propose_result(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Results in rejection");
+        ctx.set_sender(OPPONENT_ADDRESS);
+        let dispute_bytes = to_bytes(&DisputeParams {
+            battle_id: 1,
+            evidence: [1u8; 32],
+        });
+        ctx.set_parameter(&dispute_bytes);
+        let result: ContractResult<()> = contract_dispute_result(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Results in rejection");
+
+        // Void the result.
+        ctx.set_sender(ADMIN_ADDRESS);
+        let parameter_bytes = to_bytes(&ResolveDisputeParams {
+            battle_id: 1,
+            decision: DisputeDecision::Void,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_resolve_dispute(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Results in rejection");
+        let battle = host.state().battles.get(&1).map(|battle| (*battle).clone()).unwrap();
+        claim!(battle.voided, "Battle should be voided");
+        claim_eq!(host.state().get_player(&PLAYER_ADDRESS).map(|data| data.wins), Some(0));
+
+        // Propose and report the result again.
+        ctx.set_parameter(&report_bytes);
+        let result: ContractResult<()> = contract_propose_result(&ctx, &mut host, &mut logger);
+        claim_eq!(result, Err(ContractError::BattleAlreadyReported), "Battle id is voided");
+        let result: ContractResult<()> = contract_report_match(&ctx, &mut host, &mut logger);
+        claim_eq!(result, Err(ContractError::BattleAlreadyReported), "Battle id is voided");
+
+        // Dispute the voided result again.
+        ctx.set_sender(OPPONENT_ADDRESS);
+        ctx.set_parameter(&dispute_bytes);
+        let result: ContractResult<()> = contract_dispute_result(&ctx, &mut host, &mut logger);
+        claim_eq!(result, Err(ContractError::AlreadyDisputed), "Result is resolved");
+    }
+
+
+    /// Test that a reported battle that settled a challenge cannot be
+    /// disputed, since its pot has already been paid out.
+    #[concordium_test]
+    fn test_dispute_settled_challenge() {
+        // Set up the context.
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(OPPONENT_ADDRESS);
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+        let mut logger = TestLogger::init();
+        let parameter_bytes = to_bytes(&DisputeParams {
+            battle_id: 1,
+            evidence: [1u8; 32],
+        });
         ctx.set_parameter(&parameter_bytes);
 
         // Set up the state and host.
         let mut state_builder = TestStateBuilder::new();
-        let state = initial_state(&mut state_builder);
+        let mut state = initial_state(&mut state_builder);
+        add_player(&mut state, PLAYER_ADDRESS);
+        add_player(&mut state, OPPONENT_ADDRESS);
+        state.battles.insert(1, BattleRecord {
+            winner: PLAYER_ADDRESS,
+            loser: OPPONENT_ADDRESS,
+            draw: false,
+            score: None,
+            challenge: Some(0),
+            reported_at: Timestamp::from_timestamp_millis(0),
+            voided: false,
+        });
         let mut host = TestHost::new(state, state_builder);
 
-        // Call the contract function.
-        let result: ContractResult<()> = contract_set_paused(&ctx, &mut host);
+        let result: ContractResult<()> = contract_dispute_result(&ctx, &mut host, &mut logger);
+        claim_eq!(result, Err(ContractError::ChallengeSettled), "Challenge has been paid out");
+    }
 
-        // Check the result.
-        claim!(result.is_ok(), "Results in rejection");
 
-        // Check contract is paused.
-        claim_eq!(host.state().paused, true, "Smart contract should be paused");
+    /// Test that an arbitrator can uphold a disputed proposed result of a
+    /// player who has since been suspended, while the suspension still
+    /// prevents reporting new battles.
+    #[concordium_test]
+    fn test_uphold_result_of_suspended_player() {
+        // Set up the context.
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(ADMIN_ADDRESS);
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+        let mut logger = TestLogger::init();
 
-        // Set up the parameter to unpause the contract.
-        let parameter_bytes = to_bytes(&false);
+        // Set up the state and host.
+        let mut state_builder = TestStateBuilder::new();
+        let mut state = initial_state(&mut state_builder);
+        add_player(&mut state, PLAYER_ADDRESS);
+        add_player(&mut state, OPPONENT_ADDRESS);
+        let mut host = TestHost::new(state, state_builder);
+
+        // Propose the result and dispute it as the loser.
+        let parameter_bytes = to_bytes(&match_report(1));
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_propose_result(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Results in rejection");
+        ctx.set_sender(OPPONENT_ADDRESS);
+        let parameter_bytes = to_bytes(&DisputeParams {
+            battle_id: 1,
+            evidence: [1u8; 32],
+        });
         ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_dispute_result(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Results in rejection");
 
-        // Call the contract function.
-        let result: ContractResult<()> = contract_set_paused(&ctx, &mut host);
+        // Suspend the winner, who can no longer report battles.
+        host.state_mut().player_data.get_mut(&PLAYER_ADDRESS).unwrap().state =
+            PlayerState::Suspended;
+        ctx.set_sender(ADMIN_ADDRESS);
+        let parameter_bytes = to_bytes(&match_report(2));
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_report_match(&ctx, &mut host, &mut logger);
+        claim_eq!(result, Err(ContractError::PlayerSuspended), "Winner is suspended");
 
-        // Check the result.
+        // Uphold the result.
+        let parameter_bytes = to_bytes(&ResolveDisputeParams {
+            battle_id: 1,
+            decision: DisputeDecision::Uphold,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_resolve_dispute(&ctx, &mut host, &mut logger);
         claim!(result.is_ok(), "Results in rejection");
 
-        // Check contract is unpaused.
-        claim_eq!(host.state().paused, false, "Smart contract should be unpaused");
+        // Check the players and the battle.
+        let player_data = host.state().get_player(&PLAYER_ADDRESS).unwrap();
+        claim_eq!(player_data.state, PlayerState::Suspended);
+        claim_eq!((player_data.wins, player_data.losses), (1, 0));
+        let opponent_data = host.state().get_player(&OPPONENT_ADDRESS).unwrap();
+        claim_eq!((opponent_data.wins, opponent_data.losses), (0, 1));
+        claim!(host.state().battles.get(&1).is_some(), "Battle should be reported");
+        claim!(host.state().battles.get(&2).is_none(), "Battle should not be reported");
     }
 
-    /// Test that only the current admin can pause/unpause the contract.
+    /// Test that the multisig cancels a proposed admin transfer through a
+    /// proposal.
     #[concordium_test]
-    fn test_pause_not_authorized() {
+    fn test_cancel_admin_transfer_proposal() {
         // Set up the context.
         let mut ctx = TestReceiveContext::empty();
-        // NEW_ADMIN is not the current admin but tries to pause/unpause the contract.
-        ctx.set_sender(NEW_ADMIN_ADDRESS);
+        ctx.set_sender(ADMIN_ADDRESS);
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+        let mut logger = TestLogger::init();
 
-        // Set up the parameter to pause the contract.
-        let parameter_bytes = to_bytes(&true);
+        // Set up the state and host.
+        let mut state_builder = TestStateBuilder::new();
+        let mut state = initial_state(&mut state_builder);
+        state.multisig = Some(MultisigConfig {
+            signers: vec![ADMIN_ACCOUNT],
+            threshold: 1,
+        });
+        state.pending_admin = Some(PendingAdmin {
+            address: NEW_ADMIN_ADDRESS,
+            expiry: None,
+        });
+        let mut host = TestHost::new(state, state_builder);
+
+        // Cancel the transfer as the admin.
+        let result: ContractResult<()> =
+            contract_cancel_admin_transfer(&ctx, &mut host, &mut logger);
+        claim_eq!(result, Err(ContractError::MultisigRequired), "Cancel should need the multisig");
+
+        // Cancel the transfer through a proposal.
+        let parameter_bytes = to_bytes(&SubmitProposalParams {
+            action: ProposalAction::CancelAdminTransfer,
+            expiry: Timestamp::from_timestamp_millis(1_000),
+        });
         ctx.set_parameter(&parameter_bytes);
+        let result = contract_submit_proposal(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Results in rejection");
+        claim_eq!(host.state().pending_admin, None, "Pending admin should be cleared");
+        claim!(
+            logger.logs.contains(&to_bytes(&VersusEvent::AdminTransferCancelled(
+                AdminTransferCancelledEvent {
+                    pending_admin: NEW_ADMIN_ADDRESS,
+                }
+            ))),
+            "Missing event for the cancelled transfer"
+        );
+
+        // Cancel again without a pending admin.
+        let result = contract_submit_proposal(&ctx, &mut host, &mut logger);
+        claim!(
+            matches!(result, Err(ContractError::NoPendingAdmin)),
+            "Transfer should already be cancelled"
+        );
+    }
+
+    /// Test that a player waiting for approval cannot record battles until a
+    /// moderator activates it.
+    #[concordium_test]
+    fn test_pending_player_cannot_battle() {
+        // Set up the context.
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(ADMIN_ADDRESS);
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+        let mut logger = TestLogger::init();
 
         // Set up the state and host.
         let mut state_builder = TestStateBuilder::new();
-        let state = initial_state(&mut state_builder);
+        let mut state = initial_state(&mut state_builder);
+        add_player(&mut state, PLAYER_ADDRESS);
+        add_player(&mut state, OPPONENT_ADDRESS);
+        state.player_data.get_mut(&PLAYER_ADDRESS).unwrap().state = PlayerState::PendingApproval;
         let mut host = TestHost::new(state, state_builder);
 
-        // Call the contract function.
-        let result: ContractResult<()> = contract_set_paused(&ctx, &mut host);
+        // Record a result for the player.
+        let parameter_bytes = to_bytes(&UpdateBattleResultParams {
+            player: PLAYER_ADDRESS,
+            result: BattleResult::Win,
+            score: None,
+            opponent: None,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = update_battle_result(&ctx, &mut host, &mut logger);
+        claim_eq!(result, Err(ContractError::PlayerNotActive), "Player waits for approval");
 
-        // Check that invoke failed.
-        claim_eq!(
-            result,
-            Err(ContractError::Unauthorized),
-            "Pause should fail because not the current admin tries to invoke it"
-        );
+        // Report a battle of the player.
+        let parameter_bytes = to_bytes(&match_report(1));
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_report_match(&ctx, &mut host, &mut logger);
+        claim_eq!(result, Err(ContractError::PlayerNotActive), "Player waits for approval");
+
+        // Activate the player and report the battle again.
+        host.state_mut().player_data.get_mut(&PLAYER_ADDRESS).unwrap().state =
+            PlayerState::Active;
+        let result: ContractResult<()> = contract_report_match(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Results in rejection");
+        claim_eq!(host.state().get_player(&PLAYER_ADDRESS).map(|data| data.wins), Some(1));
     }
 
    